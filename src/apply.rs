@@ -0,0 +1,192 @@
+use clap::{Parser, Subcommand};
+use toml_edit::DocumentMut;
+
+use crate::errors::TomliError;
+use crate::{StringStyle, ValueType, copy, delete, move_item, set};
+
+#[derive(Parser)]
+#[command(no_binary_name = true, disable_help_subcommand = true)]
+struct Line {
+    #[command(subcommand)]
+    operation: Operation,
+}
+
+#[derive(Subcommand)]
+enum Operation {
+    /// Set a value
+    Set {
+        query: String,
+        value: String,
+        #[arg(value_enum, short = 't', long = "type", default_value_t = ValueType::Str)]
+        value_type: ValueType,
+        #[arg(value_enum, long, default_value_t = StringStyle::Auto)]
+        string_style: StringStyle,
+        #[arg(long, default_value_t = false)]
+        dotted_key: bool,
+    },
+    /// Append a value to an array
+    Append {
+        query: String,
+        value: String,
+        #[arg(value_enum, short = 't', long = "type", default_value_t = ValueType::Str)]
+        value_type: ValueType,
+        #[arg(value_enum, long, default_value_t = StringStyle::Auto)]
+        string_style: StringStyle,
+    },
+    /// Delete an item
+    Delete { query: String },
+    /// Copy an item to another place in the same document
+    Copy {
+        source_query: String,
+        destination_query: String,
+    },
+    /// Move an item to another place in the same document
+    Move {
+        source_query: String,
+        destination_query: String,
+    },
+}
+
+// Split a line into words, words can be quoted with single or double quotes
+// Backslashes are taken literally (e.g. in Windows paths), except for escaping a double quote or
+// another backslash inside of double quotes
+fn split_words(line: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', Some('"')) => {
+                let escaped = chars.next_if(|next| matches!(next, '"' | '\\'));
+                word.get_or_insert_default().push(escaped.unwrap_or(c));
+            }
+            (c, Some(q)) if c == q => quote = None,
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                word.get_or_insert_default();
+            }
+            (c, None) if c.is_whitespace() => words.extend(word.take()),
+            (c, _) => word.get_or_insert_default().push(c),
+        }
+    }
+
+    // Unterminated quote
+    if quote.is_some() {
+        return None;
+    }
+    words.extend(word);
+    Some(words)
+}
+
+fn set_options(
+    value_type: ValueType,
+    string_style: StringStyle,
+    dotted_key: bool,
+) -> set::SetOptions {
+    set::SetOptions {
+        value_type,
+        string_style,
+        int_format: crate::IntFormat::Keep,
+        group_digits: false,
+        dotted_key,
+        insert: false,
+        keep_type: false,
+        discard_decor: false,
+    }
+}
+
+fn apply(document: &mut DocumentMut, operation: Operation) -> Result<(), TomliError> {
+    match operation {
+        Operation::Set {
+            query,
+            value,
+            value_type,
+            string_style,
+            dotted_key,
+        } => set::exec(
+            document,
+            &set::Assignment {
+                query,
+                value,
+                value_type: None,
+            },
+            &set_options(value_type, string_style, dotted_key),
+        ),
+        Operation::Append {
+            query,
+            value,
+            value_type,
+            string_style,
+        } => set::exec(
+            document,
+            &set::Assignment {
+                query: format!("{query}[+]"),
+                value,
+                value_type: None,
+            },
+            &set_options(value_type, string_style, false),
+        ),
+        Operation::Delete { query } => delete::exec(document, &query).map(|_| ()),
+        Operation::Copy {
+            source_query,
+            destination_query,
+        } => {
+            let source = document.clone();
+            copy::exec(
+                &source,
+                &source_query,
+                document,
+                &destination_query,
+                false,
+                false,
+            )
+            .map(|_| ())
+        }
+        Operation::Move {
+            source_query,
+            destination_query,
+        } => move_item::exec(document, &source_query, &destination_query).map(|_| ()),
+    }
+}
+
+pub fn exec(document: &mut DocumentMut, script: &str) -> Result<String, TomliError> {
+    for (number, line) in script.lines().enumerate() {
+        let line = line.trim();
+        // Empty lines and comments are skipped
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let result = split_words(line)
+            .ok_or(TomliError::InvalidOperation(String::from(
+                "Unterminated quote",
+            )))
+            .and_then(|words| {
+                Line::try_parse_from(words).map_err(|error| {
+                    // Only the first paragraph of the clap error is relevant, the rest is usage information
+                    let error = error.to_string();
+                    let message: Vec<&str> = error
+                        .lines()
+                        .take_while(|line| !line.is_empty())
+                        .map(str::trim)
+                        .collect();
+                    TomliError::InvalidOperation(
+                        message.join(" ").trim_start_matches("error: ").to_string(),
+                    )
+                })
+            })
+            .and_then(|parsed| apply(document, parsed.operation));
+
+        if let Err(error) = result {
+            return Err(TomliError::OperationFailed(
+                number + 1,
+                line.to_string(),
+                Box::new(error),
+            ));
+        }
+    }
+
+    Ok(document.to_string())
+}
@@ -0,0 +1,26 @@
+use toml_edit::DocumentMut;
+
+use crate::errors::TomliError;
+use crate::{Format, json, yaml};
+
+pub fn exec(input: &str, from: Format, to: Format, compact: bool) -> Result<String, TomliError> {
+    // All formats are converted through a JSON value, since it can represent all of them
+    let value = match from {
+        Format::Toml => {
+            let document = input.parse::<DocumentMut>()?;
+            if matches!(to, Format::Toml) {
+                return Ok(document.to_string());
+            }
+
+            json::from_item(document.as_item())
+        }
+        Format::Json => serde_json::from_str(input)?,
+        Format::Yaml => yaml::from_str(input)?,
+    };
+
+    Ok(match to {
+        Format::Toml => json::to_document(&value)?.to_string(),
+        Format::Json => json::to_string(&value, compact),
+        Format::Yaml => yaml::to_string(&value),
+    })
+}
@@ -8,19 +8,29 @@ pub fn exec(
     destination: &mut DocumentMut,
     destination_query: &str,
     dotted_key: bool,
+    discard_decor: bool,
 ) -> Result<String, TomliError> {
     let item_to_copy = if source_query.is_empty() || source_query == "." {
         source.as_item()
     } else {
-        query::parse_toml_path(parser::evaluate(source_query)?, source.as_item())?
+        let mut paths =
+            query::expand_toml_path(&parser::evaluate(source_query)?, source.as_item())?;
+        if paths.len() != 1 {
+            return Err(TomliError::InvalidInputQuery(
+                "copy",
+                "The source query must match exactly one item",
+            ));
+        }
+        query::parse_toml_path(&paths.remove(0), source.as_item())?
     };
 
-    let item = set::parse_toml_path(
-        parser::evaluate(destination_query)?,
-        destination.as_item_mut(),
-        dotted_key,
-    )?;
-    *item = item_to_copy.clone();
+    for path in set::expand_toml_path(parser::evaluate(destination_query)?, destination.as_item())?
+    {
+        // Keep the comments and whitespace around the value that is replaced
+        let decor = set::existing_decor(&path, destination.as_item()).filter(|_| !discard_decor);
+        let item = set::parse_toml_path(&path, destination.as_item_mut(), dotted_key, false)?;
+        set::replace_item(item, item_to_copy.clone(), decor);
+    }
 
     Ok(destination.to_string())
 }
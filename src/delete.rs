@@ -2,19 +2,14 @@ use std::str;
 use toml_edit::{DocumentMut, Item, Value};
 
 use crate::errors::TomliError;
-use crate::parser;
-
-pub fn exec(document: &mut DocumentMut, query: &str) -> Result<String, TomliError> {
-    // Deleting the whole document makes no sense with this tool
-    if query == "." || query.is_empty() {
-        return Err(TomliError::InvalidInputQuery(
-            "delete",
-            "Deleting the document as a whole is currently not supported",
-        ));
-    }
+use crate::{parser, query};
 
+// Remove the item at the given path and return it
+pub(crate) fn delete_item(
+    document: &mut DocumentMut,
+    mut toml_path: Vec<parser::Item>,
+) -> Result<Item, TomliError> {
     let mut parent_item = document.as_item_mut();
-    let mut toml_path = parser::evaluate(query)?;
     let item_to_delete = toml_path.pop();
 
     for segment in toml_path.iter() {
@@ -43,37 +38,67 @@ pub fn exec(document: &mut DocumentMut, query: &str) -> Result<String, TomliErro
                     _ => return Err(TomliError::InvalidKeyAccess(index.to_string())),
                 }
             }
+            // Selectors depend on the document and need to be expanded first
+            _ => unreachable!("BUG: Selectors must be expanded before resolving a path"),
         }
     }
 
     match item_to_delete {
         Some(parser::Item::Key(key)) => {
             if let Some(parent_item) = parent_item.as_table_like_mut() {
-                if parent_item.remove(&key).is_none() {
-                    return Err(TomliError::KeyNotFound(key));
-                }
+                parent_item.remove(&key).ok_or(TomliError::KeyNotFound(key))
             } else {
-                return Err(TomliError::InvalidKeyAccess(key.to_string()));
+                Err(TomliError::InvalidKeyAccess(key.to_string()))
             }
         }
         Some(parser::Item::ArrayIndex(index)) => {
             if let Some(parent_item) = parent_item.as_array_mut() {
                 if parent_item.len() > index {
-                    parent_item.remove(index);
+                    let mut value = parent_item.remove(index);
+                    // The new first element takes over the decor of the removed first element,
+                    // which is the reverse of inserting an element at the beginning
+                    if index == 0
+                        && let Some(first) = parent_item.get_mut(0)
+                    {
+                        std::mem::swap(first.decor_mut(), value.decor_mut());
+                    }
+                    Ok(Item::Value(value))
                 } else {
-                    return Err(TomliError::IndexOutOfBounds(index));
+                    Err(TomliError::IndexOutOfBounds(index))
                 }
             } else if let Some(parent_item) = parent_item.as_array_of_tables_mut() {
                 if parent_item.len() > index {
-                    parent_item.remove(index);
+                    Ok(Item::Table(parent_item.remove(index)))
                 } else {
-                    return Err(TomliError::IndexOutOfBounds(index));
+                    Err(TomliError::IndexOutOfBounds(index))
                 }
             } else {
-                return Err(TomliError::InvalidKeyAccess(index.to_string()));
+                Err(TomliError::InvalidKeyAccess(index.to_string()))
             }
         }
-        None => {}
+        Some(_) => unreachable!("BUG: Selectors must be expanded before resolving a path"),
+        None => Ok(Item::None),
+    }
+}
+
+pub fn exec(document: &mut DocumentMut, query: &str) -> Result<String, TomliError> {
+    // Deleting the whole document makes no sense with this tool
+    if query == "." || query.is_empty() {
+        return Err(TomliError::InvalidInputQuery(
+            "delete",
+            "Deleting the document as a whole is currently not supported",
+        ));
+    }
+
+    let paths = query::expand_toml_path(&parser::evaluate(query)?, document.as_item())?;
+    // A selector that matches nothing is most likely a mistake in the query
+    if paths.is_empty() {
+        return Err(TomliError::NoMatch(query.to_string()));
+    }
+    // Delete the matches in reverse order, so that removing an array element does not shift
+    // the indices of the elements that still need to be deleted
+    for path in paths.into_iter().rev() {
+        delete_item(document, path)?;
     }
 
     Ok(document.to_string())
@@ -0,0 +1,75 @@
+use toml_edit::{DocumentMut, Item, Value};
+
+use crate::errors::TomliError;
+use crate::{json, parser, query};
+
+// Render an item as an inline TOML value without its formatting
+fn render(item: &Item) -> String {
+    match item.clone().into_value() {
+        Ok(mut value) => {
+            match &mut value {
+                Value::Array(array) => array.fmt(),
+                Value::InlineTable(table) => table.fmt(),
+                _ => (),
+            }
+            value.decor_mut().clear();
+            value.to_string()
+        }
+        Err(item) => item.to_string(),
+    }
+}
+
+// Values are equal if they have the same type and content, their representation does not matter
+fn is_equal(old: &Value, new: &Value) -> bool {
+    old.type_name() == new.type_name() && json::from_value(old) == json::from_value(new)
+}
+
+fn compare(path: &mut Vec<parser::Item>, old: &Item, new: &Item, changes: &mut Vec<String>) {
+    let is_table = old.is_table_like() && new.is_table_like();
+    let is_array = query::array_len(old).is_some() && query::array_len(new).is_some();
+
+    if is_table || is_array {
+        for (segment, old_child) in query::children(old) {
+            path.push(segment);
+            match query::parse_toml_path(&path[path.len() - 1..], new) {
+                Ok(new_child) => compare(path, old_child, new_child, changes),
+                Err(_) => changes.push(format!(
+                    "- {} = {}",
+                    parser::to_query(path),
+                    render(old_child)
+                )),
+            }
+            path.pop();
+        }
+        for (segment, new_child) in query::children(new) {
+            path.push(segment);
+            if query::parse_toml_path(&path[path.len() - 1..], old).is_err() {
+                changes.push(format!(
+                    "+ {} = {}",
+                    parser::to_query(path),
+                    render(new_child)
+                ));
+            }
+            path.pop();
+        }
+    } else if !matches!((old, new), (Item::Value(old), Item::Value(new)) if is_equal(old, new)) {
+        changes.push(format!(
+            "~ {} = {} -> {}",
+            parser::to_query(path),
+            render(old),
+            render(new)
+        ));
+    }
+}
+
+pub fn exec(document: &DocumentMut, other: &DocumentMut) -> Result<String, TomliError> {
+    let mut changes = Vec::new();
+    compare(
+        &mut Vec::new(),
+        document.as_item(),
+        other.as_item(),
+        &mut changes,
+    );
+
+    Ok(changes.join("\n"))
+}
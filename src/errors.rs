@@ -13,12 +13,20 @@ pub enum TomliError {
         you don't have enough permissions to read it: {0}"
     )]
     FileReadError(#[from] std::io::Error),
+    #[error("An error occured when trying to save the file: {0}")]
+    FileWriteError(std::io::Error),
+    #[error("Could not create a backup of the file: {0}")]
+    BackupError(std::io::Error),
     #[error("The provided TOML document has an invalid syntax:\n\n{0}")]
     TomlSyntaxError(#[from] toml_edit::TomlError),
     #[error("The provided query has an invalid syntax")]
     QuerySyntaxError(usize),
     #[error("Key not found: {0}")]
     KeyNotFound(String),
+    #[error("The query did not match anything: {0}")]
+    NoMatch(String),
+    #[error("Key already exists: {0}")]
+    KeyAlreadyExists(String),
     #[error(
         "\"{0}\" was used wrongly in the query.\n\
         This usually means that a key which has a type (e.g. key / value pair) \
@@ -27,6 +35,24 @@ pub enum TomliError {
     InvalidKeyAccess(String),
     #[error("Index (\"{0}\") is out of bounds")]
     IndexOutOfBounds(usize),
+    #[error("Index (\"-{0}\") is out of bounds")]
+    NegativeIndexOutOfBounds(usize),
+    #[error("Appending (\"[+]\") can only be used when setting a value")]
+    AppendNotAllowed,
+    #[error("Invalid assignment \"{0}\", expected QUERY=VALUE or QUERY:TYPE=VALUE")]
+    InvalidAssignment(String),
+    #[error("Invalid operation: {0}")]
+    InvalidOperation(String),
+    #[error("Operation on line {0} failed: {1}\n\n{2}")]
+    OperationFailed(usize, String, Box<TomliError>),
+    #[error("Invalid patch: {0}")]
+    InvalidPatch(&'static str),
+    #[error("The patch operation is missing the field \"{0}\"")]
+    PatchMissingField(&'static str),
+    #[error("Test failed, the value at \"{0}\" is different")]
+    PatchTestFailed(String),
+    #[error("Patch operation {0} failed:\n\n{1}")]
+    PatchOperationFailed(usize, Box<TomliError>),
     #[error("Invalid query for command {0}\n{1}")]
     InvalidInputQuery(&'static str, &'static str),
     #[error("Could not convert the given value to an integer (i64)")]
@@ -37,4 +63,26 @@ pub enum TomliError {
     ValueToBoolError(#[from] ParseBoolError),
     #[error("Could not convert the given value to a datetime")]
     ValueToDatetimeError(#[from] DatetimeParseError),
+    #[error("The given value is a valid datetime but not of type {0}")]
+    DatetimeTypeMismatch(&'static str),
+    #[error("Negative integers can only be represented in decimal")]
+    NegativeIntegerFormat,
+    #[error(
+        "--int-format and --group-digits can only be used with the types int, float, auto or toml"
+    )]
+    NumberFormatNotPossible,
+    #[error("The given value can not be represented as a {0} string")]
+    StringStyleNotPossible(&'static str),
+    #[error("Could not convert the given value to the type of the existing value ({0})")]
+    ValueTypeMismatch(&'static str),
+    #[error("Could not parse the given value as a TOML value:\n\n{0}")]
+    ValueToTomlError(toml_edit::TomlError),
+    #[error("The provided JSON document has an invalid syntax:\n\n{0}")]
+    JsonSyntaxError(#[from] serde_json::Error),
+    #[error("The provided YAML document has an invalid syntax:\n\n{0}")]
+    YamlSyntaxError(#[from] serde_norway::Error),
+    #[error("Could not convert the document to TOML: {0}")]
+    TomlConversionError(&'static str),
+    #[error("The provided regular expression is invalid:\n\n{0}")]
+    InvalidRegex(#[from] regex::Error),
 }
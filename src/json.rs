@@ -0,0 +1,136 @@
+use serde_json::{Map, Number, Value as JsonValue};
+use toml_edit::{ArrayOfTables, DocumentMut, InlineTable, Item, Table, Value};
+
+use crate::errors::TomliError;
+
+// Datetimes are converted into strings with the same format they have in TOML (RFC 3339),
+// floats that can't be represented in JSON (nan, inf) are converted into null
+pub(crate) fn from_value(value: &Value) -> JsonValue {
+    match value {
+        Value::String(string) => JsonValue::String(string.value().clone()),
+        Value::Integer(integer) => JsonValue::from(*integer.value()),
+        Value::Float(float) => Number::from_f64(*float.value())
+            .map(JsonValue::Number)
+            .unwrap_or(JsonValue::Null),
+        Value::Boolean(boolean) => JsonValue::Bool(*boolean.value()),
+        Value::Datetime(datetime) => JsonValue::String(datetime.value().to_string()),
+        Value::Array(array) => JsonValue::Array(array.iter().map(from_value).collect()),
+        Value::InlineTable(table) => JsonValue::Object(
+            table
+                .iter()
+                .map(|(key, value)| (key.to_string(), from_value(value)))
+                .collect::<Map<_, _>>(),
+        ),
+    }
+}
+
+pub(crate) fn from_table(table: &Table) -> JsonValue {
+    JsonValue::Object(
+        table
+            .iter()
+            .map(|(key, item)| (key.to_string(), from_item(item)))
+            .collect::<Map<_, _>>(),
+    )
+}
+
+/// Convert a TOML item into a JSON value
+pub fn from_item(item: &Item) -> JsonValue {
+    match item {
+        Item::None => JsonValue::Null,
+        Item::Value(value) => from_value(value),
+        Item::Table(table) => from_table(table),
+        Item::ArrayOfTables(array) => JsonValue::Array(array.iter().map(from_table).collect()),
+    }
+}
+
+/// Serialize a JSON value either pretty printed or on a single line
+pub fn to_string(value: &JsonValue, compact: bool) -> String {
+    if compact {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
+    .expect("BUG: Could not serialize JSON value")
+}
+
+fn to_value(value: &JsonValue) -> Result<Value, TomliError> {
+    Ok(match value {
+        JsonValue::Null => {
+            return Err(TomliError::TomlConversionError(
+                "Null values can not be represented in TOML",
+            ));
+        }
+        JsonValue::Bool(boolean) => Value::from(*boolean),
+        JsonValue::Number(number) => {
+            if let Some(integer) = number.as_i64() {
+                Value::from(integer)
+            } else if number.is_u64() {
+                return Err(TomliError::TomlConversionError(
+                    "Integers larger than a 64-bit signed integer can not be represented in TOML",
+                ));
+            } else {
+                Value::from(number.as_f64().expect("BUG: Expected a float"))
+            }
+        }
+        JsonValue::String(string) => Value::from(string.as_str()),
+        JsonValue::Array(array) => {
+            Value::Array(array.iter().map(to_value).collect::<Result<_, _>>()?)
+        }
+        JsonValue::Object(object) => {
+            let mut table = InlineTable::new();
+            for (key, value) in object {
+                table.insert(key, to_value(value)?);
+            }
+
+            Value::InlineTable(table)
+        }
+    })
+}
+
+fn to_table(object: &Map<String, JsonValue>) -> Result<Table, TomliError> {
+    let mut table = Table::new();
+    for (key, value) in object {
+        table.insert(key, to_item(value)?);
+    }
+    // Tables that only contain other tables don't need their own header
+    table.set_implicit(
+        !table.is_empty()
+            && table
+                .iter()
+                .all(|(_, item)| item.is_table() || item.is_array_of_tables()),
+    );
+
+    Ok(table)
+}
+
+// Objects are converted into tables and arrays of objects into arrays of tables
+// Everything that is nested inside of an array is converted into inline values
+pub(crate) fn to_item(value: &JsonValue) -> Result<Item, TomliError> {
+    match value {
+        JsonValue::Object(object) => Ok(Item::Table(to_table(object)?)),
+        JsonValue::Array(array) if !array.is_empty() && array.iter().all(JsonValue::is_object) => {
+            let mut array_of_tables = ArrayOfTables::new();
+            for object in array.iter().filter_map(JsonValue::as_object) {
+                array_of_tables.push(to_table(object)?);
+            }
+
+            Ok(Item::ArrayOfTables(array_of_tables))
+        }
+        value => Ok(Item::Value(to_value(value)?)),
+    }
+}
+
+/// Convert a JSON object into a TOML document
+pub fn to_document(value: &JsonValue) -> Result<DocumentMut, TomliError> {
+    let JsonValue::Object(object) = value else {
+        return Err(TomliError::TomlConversionError(
+            "Only objects can be converted into a TOML document",
+        ));
+    };
+
+    let mut document = DocumentMut::new();
+    *document.as_table_mut() = to_table(object)?;
+    document.as_table_mut().set_implicit(false);
+
+    Ok(document)
+}
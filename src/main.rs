@@ -1,13 +1,21 @@
+mod apply;
+mod convert;
 mod copy;
 mod delete;
+mod diff;
 mod errors;
+mod json;
+mod merge;
+mod move_item;
 mod parser;
+mod patch;
 mod query;
 mod set;
+mod yaml;
 
-use std::fs::File;
+use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str;
 
 use clap::{Parser, Subcommand};
@@ -26,6 +34,24 @@ struct Cli {
     /// Edit file in-place when applicable
     #[arg(short = 'i', long, global = true)]
     in_place: bool,
+    /// Keep a copy of the original file with the given suffix (default: .bak)
+    ///
+    /// This is only relevant if --in-place is set
+    #[arg(
+        long,
+        global = true,
+        value_name = "SUFFIX",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = ".bak",
+        value_parser = clap::builder::NonEmptyStringValueParser::new()
+    )]
+    backup: Option<String>,
+    /// Do not modify anything, print a unified diff of the changes instead
+    ///
+    /// Exits with 1 if the document would be changed and with 2 if an error occurred
+    #[arg(long, visible_alias = "diff", global = true)]
+    check: bool,
     /// Do not add trailing newline
     ///
     /// This is only relevant if --in-place is not set
@@ -41,16 +67,61 @@ enum Commands {
     Query {
         /// Query expression
         query: String,
+        /// Print strings without quotes and arrays with one element per line
+        #[arg(short = 'r', long)]
+        raw: bool,
+        /// Format that should be used for printing the result
+        #[arg(value_enum, short = 'o', long = "output", default_value_t = Format::Toml)]
+        output_format: Format,
+        /// Print JSON on a single line instead of pretty printing it
+        ///
+        /// This is only relevant if --output is set to json
+        #[arg(short = 'c', long)]
+        compact: bool,
+    },
+    /// Convert a document between TOML, JSON and YAML
+    Convert {
+        /// Format of the input document
+        #[arg(value_enum, long, default_value_t = Format::Toml)]
+        from: Format,
+        /// Format of the output document
+        #[arg(value_enum, long, default_value_t = Format::Json)]
+        to: Format,
+        /// Print JSON on a single line instead of pretty printing it
+        ///
+        /// This is only relevant if --to is set to json
+        #[arg(short = 'c', long)]
+        compact: bool,
     },
     /// Edit a TOML document
     Set {
         /// Query expression that specifies which element you want to set / append
-        query: String,
+        #[arg(required_unless_present = "assignments", requires = "value")]
+        query: Option<String>,
         /// Value to set
-        value: String,
+        value: Option<String>,
+        /// Additional value to set in the form QUERY=VALUE or QUERY:TYPE=VALUE
+        ///
+        /// Can be used multiple times. All values are set in the given order
+        /// before the document is written.
+        #[arg(short = 'a', long = "assign", value_name = "QUERY=VALUE")]
+        assignments: Vec<set::Assignment>,
         /// Value type that should be used
         #[arg(value_enum, short = 't', long = "type", default_value_t = ValueType::Str)]
         value_type: ValueType,
+        /// How values of type str should be quoted
+        ///
+        /// auto picks the most readable style that can represent the value.
+        #[arg(value_enum, long, default_value_t = StringStyle::Auto)]
+        string_style: StringStyle,
+        /// Representation of integers
+        ///
+        /// keep uses the representation of the given value (e.g. 0o755 stays octal).
+        #[arg(value_enum, long, default_value_t = IntFormat::Keep)]
+        int_format: IntFormat,
+        /// Separate groups of digits in integers and floats with underscores (e.g. 1_000_000)
+        #[arg(long, default_value_t = false)]
+        group_digits: bool,
         /// Use dotted keys instead of creating a subtable
         ///
         /// By default, tomli expands dotted keys into a subtable.
@@ -66,6 +137,68 @@ enum Commands {
         /// windows.workspace = true
         #[arg(verbatim_doc_comment, long, default_value_t = false)]
         dotted_key: bool,
+        /// Insert the value into the array instead of overwriting the element at the index
+        ///
+        /// The elements starting at the last index in the query are shifted to the right.
+        #[arg(long, default_value_t = false)]
+        insert: bool,
+        /// Convert the value to the type of the value that is being overwritten
+        ///
+        /// If there is no existing value, then the type from --type is used.
+        #[arg(long, default_value_t = false)]
+        keep_type: bool,
+        /// Do not keep the comments and whitespace around the value that is replaced
+        #[arg(long, default_value_t = false)]
+        discard_decor: bool,
+    },
+    /// Move or rename an item in a TOML document
+    ///
+    /// Renamed keys keep their position in the table. The comments and whitespace of the item
+    /// are moved with it.
+    #[command(alias = "rename")]
+    Move {
+        /// Query expression that specifies which item you want to move
+        source_query: String,
+        /// Query expression that specifies where the item should be moved to
+        destination_query: String,
+    },
+    /// Compare a TOML document with another TOML document
+    ///
+    /// Every added (+), removed (-) and changed (~) item is printed with its query.
+    /// Formatting and the order of keys are ignored.
+    /// The exit code is 0 if the documents are equal, 1 if they differ and 2 if an error occurred.
+    Diff {
+        /// TOML document that the input document is compared with
+        other: PathBuf,
+    },
+    /// Merge another TOML document into a TOML document
+    ///
+    /// Tables are merged key by key and values that exist in both documents are overwritten.
+    Merge {
+        /// TOML document that is merged into the input document
+        overlay: PathBuf,
+        /// Query expression that specifies where the document should be merged into
+        #[arg(default_value = ".")]
+        query: String,
+        /// How arrays that exist in both documents are merged
+        #[arg(value_enum, long, default_value_t = ArrayStrategy::Replace)]
+        arrays: ArrayStrategy,
+    },
+    /// Apply a JSON patch (RFC 6902) to a TOML document
+    ///
+    /// Paths are JSON pointers (RFC 6901). The document is only written if all operations,
+    /// including tests, succeed.
+    Patch {
+        /// File that contains the JSON patch
+        patch: PathBuf,
+    },
+    /// Apply a list of operations from a file to a TOML document
+    ///
+    /// Every line contains one operation (set, append, delete, copy or move) followed by its
+    /// arguments. The document is only written if all operations succeed.
+    Apply {
+        /// File that contains the operations
+        script: PathBuf,
     },
     /// Delete an item in a TOML document
     Delete {
@@ -98,6 +231,9 @@ enum Commands {
         /// windows.workspace = true
         #[arg(verbatim_doc_comment, long, default_value_t = false)]
         dotted_key: bool,
+        /// Do not keep the comments and whitespace around the value that is replaced
+        #[arg(long, default_value_t = false)]
+        discard_decor: bool,
     },
 }
 
@@ -108,35 +244,153 @@ enum ValueType {
     Float,
     Bool,
     Datetime,
+    Date,
+    Time,
+    LocalDatetime,
+    OffsetDatetime,
+    Toml,
+    Auto,
 }
 
-// Read the input document from a file
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum StringStyle {
+    Auto,
+    Basic,
+    Literal,
+    MultilineBasic,
+    MultilineLiteral,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum IntFormat {
+    Keep,
+    Decimal,
+    Hex,
+    Octal,
+    Binary,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum ArrayStrategy {
+    /// Use the array of the merged document
+    Replace,
+    /// Add all elements of the merged document
+    Append,
+    /// Add the elements of the merged document that don't exist yet
+    Union,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+enum Format {
+    Toml,
+    Json,
+    Yaml,
+}
+
+// Read the raw input from a file
 // If the user did not provide a file, then read from stdin
-fn read_input(filepath: Option<&PathBuf>) -> Result<DocumentMut, TomliError> {
-    let input = if let Some(filepath) = filepath {
+fn read_input_string(filepath: Option<&PathBuf>) -> Result<String, TomliError> {
+    Ok(if let Some(filepath) = filepath {
         std::fs::read_to_string(filepath)?
     } else {
         std::io::read_to_string(std::io::stdin())?
-    };
+    })
+}
+
+// Read the input document from a file
+// If the user did not provide a file, then read from stdin
+fn read_input(filepath: Option<&PathBuf>) -> Result<DocumentMut, TomliError> {
+    Ok(read_input_string(filepath)?.parse::<DocumentMut>()?)
+}
+
+// Write the content into a temporary file next to the original and then rename it, so that
+// the original file is either completely replaced or not touched at all
+// If a backup suffix is given, then the original file is copied before it is replaced
+fn write_file(filepath: &Path, content: &str, backup: Option<&str>) -> Result<(), TomliError> {
+    // Symlinks are followed, so that the link itself stays intact
+    // The file does not have to exist yet, since the copy command can create it
+    let filepath = std::fs::canonicalize(filepath).unwrap_or_else(|_| filepath.to_path_buf());
+    let metadata = std::fs::metadata(&filepath).ok();
+    let file_name = filepath
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let temporary_path =
+        filepath.with_file_name(format!(".{file_name}.tomli-{}", std::process::id()));
+
+    if let Some(suffix) = backup.filter(|_| metadata.is_some()) {
+        let mut backup_path = filepath.clone().into_os_string();
+        backup_path.push(suffix);
+        std::fs::copy(&filepath, &backup_path).map_err(TomliError::BackupError)?;
+    }
+
+    let result = (|| {
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&temporary_path)?;
+        file.write_all(content.as_bytes())?;
+        if let Some(metadata) = &metadata {
+            file.set_permissions(metadata.permissions())?;
+            #[cfg(target_family = "unix")]
+            {
+                use std::os::unix::fs::MetadataExt;
+                // Changing the owner requires privileges, so this is only done on a best effort basis
+                let _ =
+                    std::os::unix::fs::fchown(&file, Some(metadata.uid()), Some(metadata.gid()));
+            }
+        }
+        file.sync_all()?;
+        std::fs::rename(&temporary_path, &filepath)
+    })();
+
+    if let Err(error) = result {
+        let _ = std::fs::remove_file(&temporary_path);
+        return Err(TomliError::FileWriteError(error));
+    }
 
-    Ok(input.parse::<DocumentMut>()?)
+    // Make sure that the rename itself is persisted
+    #[cfg(target_family = "unix")]
+    if let Some(directory) = filepath.parent() {
+        File::open(directory)
+            .and_then(|directory| directory.sync_all())
+            .map_err(TomliError::FileWriteError)?;
+    }
+
+    Ok(())
 }
 
 fn main() {
     let cli = Cli::parse();
-    let mut document = read_input(cli.filepath.as_ref()).unwrap_or_else(|err| {
+    let input = read_input_string(cli.filepath.as_ref()).unwrap_or_else(|err| {
         eprintln!("{}", err);
         std::process::exit(1);
     });
+    // The convert command parses the input by itself, since it does not have to be TOML
+    let mut document = if matches!(cli.command, Commands::Convert { .. }) {
+        DocumentMut::new()
+    } else {
+        input
+            .parse::<DocumentMut>()
+            .map_err(TomliError::from)
+            .unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            })
+    };
 
+    // The document that would be modified, which is only different from the input for copy
+    let mut original = input.clone();
     let (query, result, can_write, filepath) = match cli.command {
         Commands::Copy {
             source_query,
             destination,
             destination_query,
             dotted_key,
+            discard_decor,
         } => {
-            let mut destination_document = read_input(Some(&destination)).unwrap_or_default();
+            original = read_input_string(Some(&destination)).unwrap_or_default();
+            let mut destination_document = original.parse::<DocumentMut>().unwrap_or_default();
 
             (
                 source_query.clone(),
@@ -146,25 +400,116 @@ fn main() {
                     &mut destination_document,
                     &destination_query,
                     dotted_key,
+                    discard_decor,
                 ),
                 true,
                 Some(destination.clone()),
             )
         }
-        Commands::Query { query } => (
+        Commands::Query {
+            query,
+            raw,
+            output_format,
+            compact,
+        } => (
             query.clone(),
-            query::exec(&document, &query),
+            query::exec(&document, &query, raw, output_format, compact),
+            false,
+            cli.filepath,
+        ),
+        Commands::Convert { from, to, compact } => (
+            String::new(),
+            convert::exec(&input, from, to, compact),
             false,
             cli.filepath,
         ),
         Commands::Set {
             query,
             value,
+            assignments,
             value_type,
+            string_style,
+            int_format,
+            group_digits,
             dotted_key,
+            insert,
+            keep_type,
+            discard_decor,
+        } => {
+            let options = set::SetOptions {
+                value_type,
+                string_style,
+                int_format,
+                group_digits,
+                dotted_key,
+                insert,
+                keep_type,
+                discard_decor,
+            };
+            // The positional query and value are applied before the additional assignments
+            let assignment = query.zip(value).map(|(query, value)| set::Assignment {
+                query,
+                value,
+                value_type: None,
+            });
+            // Remember the query that is currently applied, so that errors can point to it
+            let mut query = String::new();
+            let result = assignment
+                .iter()
+                .chain(assignments.iter())
+                .try_for_each(|assignment| {
+                    query.clone_from(&assignment.query);
+                    set::exec(&mut document, assignment, &options)
+                })
+                .map(|_| document.to_string());
+            (query, result, true, cli.filepath)
+        }
+        Commands::Move {
+            source_query,
+            destination_query,
+        } => (
+            source_query.clone(),
+            move_item::exec(&mut document, &source_query, &destination_query),
+            true,
+            cli.filepath,
+        ),
+        Commands::Diff { other } => {
+            match read_input(Some(&other)).and_then(|other| diff::exec(&document, &other)) {
+                Ok(changes) if changes.is_empty() => std::process::exit(0),
+                Ok(changes) => {
+                    if cli.strip_trailing_newline {
+                        print!("{changes}");
+                    } else {
+                        println!("{changes}");
+                    }
+                    std::process::exit(1);
+                }
+                Err(error) => {
+                    eprintln!("{error}");
+                    std::process::exit(2);
+                }
+            }
+        }
+        Commands::Merge {
+            overlay,
+            query,
+            arrays,
         } => (
             query.clone(),
-            set::exec(&mut document, &query, &value, value_type, dotted_key),
+            read_input(Some(&overlay))
+                .and_then(|overlay| merge::exec(&mut document, &overlay, &query, &arrays)),
+            true,
+            cli.filepath,
+        ),
+        Commands::Patch { patch } => (
+            String::new(),
+            read_input_string(Some(&patch)).and_then(|patch| patch::exec(&mut document, &patch)),
+            true,
+            cli.filepath,
+        ),
+        Commands::Apply { script } => (
+            String::new(),
+            read_input_string(Some(&script)).and_then(|script| apply::exec(&mut document, &script)),
             true,
             cli.filepath,
         ),
@@ -188,17 +533,31 @@ fn main() {
                 ),
                 _ => eprintln!("{}", error),
             };
-            std::process::exit(1);
+            std::process::exit(if can_write && cli.check { 2 } else { 1 });
         }
         Ok(result) => {
-            if can_write
+            if can_write && cli.check {
+                if result == original {
+                    std::process::exit(0);
+                }
+                let name = filepath.map_or(String::from("stdin"), |filepath| {
+                    filepath.to_string_lossy().to_string()
+                });
+                print!(
+                    "{}",
+                    similar::TextDiff::from_lines(&original, &result)
+                        .unified_diff()
+                        .header(&name, &name)
+                );
+                std::process::exit(1);
+            } else if can_write
                 && cli.in_place
                 && let Some(filepath) = filepath
             {
-                let mut file =
-                    File::create(filepath).expect("An error occured when trying to save the file");
-                file.write_all(result.as_bytes())
-                    .expect("An error occured when trying to save the file");
+                if let Err(error) = write_file(&filepath, &result, cli.backup.as_deref()) {
+                    eprintln!("{error}");
+                    std::process::exit(1);
+                }
             } else if cli.strip_trailing_newline {
                 print!("{result}");
             } else {
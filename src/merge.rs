@@ -0,0 +1,165 @@
+use toml_edit::{Array, ArrayOfTables, DocumentMut, Item, Value};
+
+use crate::errors::TomliError;
+use crate::{ArrayStrategy, json, move_item, parser, query, set};
+
+// Append a value to an array with the same formatting as its other elements
+fn push_value(array: &mut Array, value: &Value) {
+    if array.is_empty() {
+        let mut value = value.clone();
+        value.decor_mut().clear();
+        array.push(value);
+    } else {
+        // Replacing the placeholder keeps its decor
+        set::insert_placeholder(array, array.len());
+        array.replace(array.len() - 1, value.clone());
+    }
+}
+
+// The first table of a document has no empty line in front of it, which looks off once it is
+// placed after other tables, so it gets the default decor instead
+fn clear_leading_decor(item: &mut Item) {
+    let tables: Vec<&mut toml_edit::Table> = match item {
+        Item::Table(table) => vec![table],
+        Item::ArrayOfTables(array) => array.iter_mut().collect(),
+        _ => return,
+    };
+    for table in tables {
+        if table.decor().prefix().and_then(|prefix| prefix.as_str()) == Some("") {
+            table.decor_mut().clear();
+        }
+        table
+            .iter_mut()
+            .for_each(|(_, child)| clear_leading_decor(child));
+    }
+}
+
+fn merge_array(base: &mut Array, overlay: &Array, strategy: &ArrayStrategy) {
+    if matches!(strategy, ArrayStrategy::Replace) {
+        base.clear();
+    }
+    for value in overlay.iter() {
+        // Values are compared by their content and not by their representation
+        let is_duplicate = matches!(strategy, ArrayStrategy::Union)
+            && base
+                .iter()
+                .any(|existing| json::from_value(existing) == json::from_value(value));
+        if !is_duplicate {
+            push_value(base, value);
+        }
+    }
+}
+
+fn merge_array_of_tables(
+    base: &mut ArrayOfTables,
+    overlay: &ArrayOfTables,
+    strategy: &ArrayStrategy,
+) {
+    if matches!(strategy, ArrayStrategy::Replace) {
+        base.clear();
+    }
+    for table in overlay.iter() {
+        let is_duplicate = matches!(strategy, ArrayStrategy::Union)
+            && base
+                .iter()
+                .any(|existing| json::from_table(existing) == json::from_table(table));
+        if !is_duplicate {
+            let mut table = Item::Table(table.clone());
+            move_item::reset_position(&mut table);
+            base.push(table.into_table().expect("BUG: Item must be a table"));
+        }
+    }
+}
+
+// Merge `overlay` into `base`
+// Tables are merged key by key, arrays depending on the strategy and everything else is replaced
+fn merge_item(base: &mut Item, overlay: &Item, strategy: &ArrayStrategy) {
+    match (base, overlay) {
+        (Item::Value(Value::Array(base)), Item::Value(Value::Array(overlay))) => {
+            merge_array(base, overlay, strategy)
+        }
+        (Item::ArrayOfTables(base), Item::ArrayOfTables(overlay)) => {
+            merge_array_of_tables(base, overlay, strategy)
+        }
+        (base, overlay) if base.is_table_like() && overlay.is_table_like() => {
+            let is_inline = base.is_value();
+            let base = base
+                .as_table_like_mut()
+                .expect("BUG: Item must be table like");
+            for (key, overlay_item) in overlay
+                .as_table_like()
+                .expect("BUG: Item must be table like")
+                .iter()
+            {
+                if let Some(base_item) = base.get_mut(key) {
+                    merge_item(base_item, overlay_item, strategy);
+                    continue;
+                }
+
+                let mut item = overlay_item.clone();
+                move_item::reset_position(&mut item);
+                clear_leading_decor(&mut item);
+                // Inline tables can only contain values
+                if is_inline && let Ok(value) = item.clone().into_value() {
+                    item = Item::Value(value);
+                }
+                let key = overlay
+                    .as_table_like()
+                    .and_then(|table| table.key(key))
+                    .expect("BUG: Could not find key that was just listed");
+                base.entry_format(key).or_insert(item);
+            }
+        }
+        (base, overlay) => {
+            // The replaced value keeps its position in the document
+            let decor = base.as_value().map(|value| value.decor().clone());
+            let mut item = overlay.clone();
+            move_item::reset_position(&mut item);
+            if base.is_value()
+                && let Ok(value) = item.clone().into_value()
+            {
+                item = Item::Value(value);
+            }
+            set::replace_item(base, item, decor);
+        }
+    }
+}
+
+pub fn exec(
+    document: &mut DocumentMut,
+    overlay: &DocumentMut,
+    query: &str,
+    strategy: &ArrayStrategy,
+) -> Result<String, TomliError> {
+    if query.is_empty() || query == "." {
+        merge_item(document.as_item_mut(), overlay.as_item(), strategy);
+        return Ok(document.to_string());
+    }
+
+    for path in set::expand_toml_path(parser::evaluate(query)?, document.as_item())? {
+        let existing_depth = (0..=path.len())
+            .rev()
+            .find(|depth| query::parse_toml_path(&path[..*depth], document.as_item()).is_ok())
+            .unwrap_or_default();
+        let item = set::parse_toml_path(&path, document.as_item_mut(), false, false)?;
+        if existing_depth == path.len() {
+            merge_item(item, overlay.as_item(), strategy);
+            continue;
+        }
+
+        // Paths that do not exist yet are created and get a copy of the overlay
+        *item = overlay.as_item().clone();
+        move_item::reset_position(item);
+        clear_leading_decor(item);
+        // Tables that were created for the path should not have their own header
+        for depth in existing_depth + 1..=path.len() {
+            if let Item::Table(table) =
+                set::parse_toml_path(&path[..depth], document.as_item_mut(), false, false)?
+            {
+                table.set_implicit(true);
+            }
+        }
+    }
+
+    Ok(document.to_string())
+}
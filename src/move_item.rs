@@ -0,0 +1,186 @@
+use toml_edit::{DocumentMut, Item, Key, TableLike};
+
+use crate::errors::TomliError;
+use crate::{delete, parser, query, set};
+
+// Check if two concrete paths point to the same item
+pub(crate) fn same_path(first: &[parser::Item], second: &[parser::Item]) -> bool {
+    first.len() == second.len()
+        && first.iter().zip(second).all(|segment| match segment {
+            (parser::Item::Key(first), parser::Item::Key(second)) => first == second,
+            (parser::Item::ArrayIndex(first), parser::Item::ArrayIndex(second)) => first == second,
+            _ => false,
+        })
+}
+
+// Rename a key without changing its position in the table
+fn rename_key(table: &mut dyn TableLike, old_key: &str, new_key: &str) {
+    let keys: Vec<Key> = table
+        .iter()
+        .filter_map(|(key, _)| table.key(key).cloned())
+        .collect();
+
+    // Every entry is removed and inserted again, so that the order is kept
+    for key in keys {
+        let item = table
+            .remove(key.get())
+            .expect("BUG: Could not find key that was just listed");
+        let key = if key.get() == old_key {
+            Key::new(new_key)
+                .with_leaf_decor(key.leaf_decor().clone())
+                .with_dotted_decor(key.dotted_decor().clone())
+        } else {
+            key
+        };
+        table.entry_format(&key).or_insert(item);
+    }
+}
+
+// Forget the position of a table and its subtables, so that they are placed after their new parent
+pub(crate) fn reset_position(item: &mut Item) {
+    match item {
+        Item::Table(table) => {
+            table.set_position(None);
+            table.iter_mut().for_each(|(_, item)| reset_position(item));
+        }
+        Item::ArrayOfTables(array) => array.iter_mut().for_each(|table| {
+            table.set_position(None);
+            table.iter_mut().for_each(|(_, item)| reset_position(item));
+        }),
+        _ => (),
+    }
+}
+
+// Resolve a single concrete path from the query
+fn single_path(
+    mut paths: Vec<Vec<parser::Item>>,
+    message: &'static str,
+) -> Result<Vec<parser::Item>, TomliError> {
+    if paths.len() != 1 {
+        return Err(TomliError::InvalidInputQuery("move", message));
+    }
+    Ok(paths.remove(0))
+}
+
+pub fn exec(
+    document: &mut DocumentMut,
+    source_query: &str,
+    destination_query: &str,
+) -> Result<String, TomliError> {
+    // Moving the whole document makes no sense
+    if source_query == "." || source_query.is_empty() {
+        return Err(TomliError::InvalidInputQuery(
+            "move",
+            "Moving the document as a whole is currently not supported",
+        ));
+    }
+
+    let source = single_path(
+        query::expand_toml_path(&parser::evaluate(source_query)?, document.as_item())?,
+        "The source query must match exactly one item",
+    )?;
+    let mut destination = single_path(
+        set::expand_toml_path(parser::evaluate(destination_query)?, document.as_item())?,
+        "The destination query must match exactly one item",
+    )?;
+    let Some(destination_segment) = destination.pop() else {
+        return Err(TomliError::InvalidInputQuery(
+            "move",
+            "Replacing the document as a whole is currently not supported",
+        ));
+    };
+
+    if destination.len() >= source.len() && same_path(&source, &destination[..source.len()]) {
+        return Err(TomliError::InvalidInputQuery(
+            "move",
+            "An item can not be moved into itself",
+        ));
+    }
+
+    let (source_parent, source_segment) = source.split_at(source.len() - 1);
+    if let parser::Item::Key(new_key) = &destination_segment {
+        let parent = query::parse_toml_path(&destination, document.as_item()).ok();
+        if parent.and_then(|parent| parent.get(new_key)).is_some() {
+            return Err(TomliError::KeyAlreadyExists(new_key.to_string()));
+        }
+
+        // Keys that stay in the same table are renamed in place to keep their position
+        if let [parser::Item::Key(old_key)] = source_segment
+            && same_path(source_parent, &destination)
+        {
+            let parent = set::parse_toml_path(source_parent, document.as_item_mut(), false, false)?;
+            if let Some(table) = parent.as_table_like_mut() {
+                rename_key(table, old_key, new_key);
+                return Ok(document.to_string());
+            }
+        }
+    }
+
+    // The key of the source holds the comments above it, so they need to be moved as well
+    let source_key = match source_segment {
+        [parser::Item::Key(key)] => query::parse_toml_path(source_parent, document.as_item())?
+            .as_table_like()
+            .and_then(|table| table.key(key))
+            .cloned(),
+        _ => None,
+    };
+    let source_parent_item = query::parse_toml_path(source_parent, document.as_item())?;
+    let source_parent_kind = (source_parent_item.is_value(), source_parent_item.is_array());
+    let mut item = delete::delete_item(document, source.clone())?;
+    reset_position(&mut item);
+
+    destination.push(destination_segment.clone());
+    // Moving an item to an index shifts the existing elements instead of overwriting them
+    let insert = matches!(destination_segment, parser::Item::ArrayIndex(_));
+    set::parse_toml_path(&destination, document.as_item_mut(), false, insert)?;
+    destination.pop();
+
+    let parent = set::parse_toml_path(&destination, document.as_item_mut(), false, false)?;
+    // Tables inside of inline tables and arrays have to be inline tables themselves
+    let mut item = if parent.is_value() {
+        Item::Value(item.into_value().map_err(|_| {
+            TomliError::InvalidInputQuery("move", "This item can not be converted to a value")
+        })?)
+    } else {
+        item
+    };
+    // Tables, inline tables and arrays format their entries differently, so the decor is only
+    // kept if the item stays in the same kind of parent
+    let keep_decor = source_parent_kind == (parent.is_value(), parent.is_array());
+    if !keep_decor && let Some(value) = item.as_value_mut() {
+        value.decor_mut().clear();
+    }
+
+    if let (parser::Item::Key(new_key), Some(source_key)) = (&destination_segment, source_key)
+        && keep_decor
+        && let Some(mut key) = parent
+            .as_table_like_mut()
+            .and_then(|table| table.key_mut(new_key))
+    {
+        *key.leaf_decor_mut() = source_key.leaf_decor().clone();
+    }
+
+    let is_key = matches!(destination_segment, parser::Item::Key(_));
+    let target = match destination_segment {
+        parser::Item::Key(key) => parent.get_mut(key),
+        parser::Item::ArrayIndex(index) => parent.get_mut(index),
+        _ => {
+            let last = parent
+                .as_array()
+                .map(|array| array.len())
+                .or_else(|| parent.as_array_of_tables().map(|array| array.len()))
+                .unwrap_or_default();
+            parent.get_mut(last.saturating_sub(1))
+        }
+    }
+    .expect("BUG: Could not find item that was just created");
+
+    // Elements in arrays take over the decor of their placeholder to match their neighbours
+    let decor = target
+        .as_value()
+        .filter(|_| !is_key)
+        .map(|value| value.decor().clone());
+    set::replace_item(target, item, decor);
+
+    Ok(document.to_string())
+}
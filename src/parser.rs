@@ -1,11 +1,65 @@
+use std::iter::Peekable;
+use std::slice::Iter;
 use std::str::Chars;
 
+use regex::Regex;
+use toml_edit::Value;
+
 use crate::TomliError;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+pub enum Operator {
+    Equal,
+    NotEqual,
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    Matches(Regex),
+}
+
+/// Condition that an element of an array has to fulfill to be selected
+#[derive(Debug, Clone)]
+pub struct Filter {
+    /// The filter expression as it was written in the query
+    pub expression: String,
+    /// Key of the value inside the element that should be compared
+    pub key: String,
+    pub operator: Operator,
+    pub value: Value,
+}
+
+#[derive(Debug, Clone)]
 pub enum Item {
     Key(String),
     ArrayIndex(usize),
+    /// Index that is counted from the end of an array (e.g. 1 is the last element)
+    NegativeArrayIndex(usize),
+    /// Range of array elements, bounds can be negative to count from the end
+    Slice(Option<isize>, Option<isize>),
+    /// Appends a new element to an array (only used when setting a value)
+    Append,
+    /// Matches every key of a table or every element of an array
+    Wildcard,
+    /// Matches every item with the given key at any depth
+    RecursiveDescent(String),
+    /// Matches every element of an array that fulfills the filter condition
+    Filter(Box<Filter>),
+}
+
+impl Item {
+    /// Whether this item can only be resolved by looking at the document
+    pub fn is_selector(&self) -> bool {
+        !matches!(self, Item::Key(_) | Item::ArrayIndex(_) | Item::Append)
+    }
+
+    /// Whether this item can match more than one TOML item
+    pub fn matches_multiple(&self) -> bool {
+        matches!(
+            self,
+            Item::Wildcard | Item::Slice(_, _) | Item::RecursiveDescent(_) | Item::Filter(_)
+        )
+    }
 }
 
 #[derive(PartialEq, Debug)]
@@ -13,11 +67,30 @@ enum Token {
     OpenBracket,
     CloseBracket,
     Dot,
+    Asterisk,
+    Plus,
+    Colon,
+    QuestionMark,
     Char(char),
     Number(char),
     Unkown(char),
 }
 
+impl Token {
+    fn as_char(&self) -> char {
+        match self {
+            Token::OpenBracket => '[',
+            Token::CloseBracket => ']',
+            Token::Dot => '.',
+            Token::Asterisk => '*',
+            Token::Plus => '+',
+            Token::Colon => ':',
+            Token::QuestionMark => '?',
+            Token::Char(c) | Token::Number(c) | Token::Unkown(c) => *c,
+        }
+    }
+}
+
 fn tokenize(chars: Chars) -> Vec<Token> {
     let (lo, _) = chars.size_hint();
     let mut tokens = Vec::with_capacity(lo);
@@ -27,6 +100,10 @@ fn tokenize(chars: Chars) -> Vec<Token> {
             '.' => Token::Dot,
             '[' => Token::OpenBracket,
             ']' => Token::CloseBracket,
+            '*' => Token::Asterisk,
+            '+' => Token::Plus,
+            ':' => Token::Colon,
+            '?' => Token::QuestionMark,
             c if c.is_alphabetic()
                 || ['_', '-', '"', '\'', '(', ')', '=', ','].contains(&c)
                 || c.is_whitespace() =>
@@ -41,6 +118,126 @@ fn tokenize(chars: Chars) -> Vec<Token> {
     tokens
 }
 
+// Convert the content of a pair of brackets into an index, negative index or slice
+fn parse_index(index: &str) -> Option<Item> {
+    if let Some((start, end)) = index.split_once(':') {
+        let parse_bound = |bound: &str| {
+            if bound.is_empty() {
+                Some(None)
+            } else {
+                bound.parse().ok().map(Some)
+            }
+        };
+
+        Some(Item::Slice(parse_bound(start)?, parse_bound(end)?))
+    } else if let Some(index) = index.strip_prefix('-') {
+        // "-0" does not point to any element
+        index
+            .parse()
+            .ok()
+            .filter(|index| *index > 0)
+            .map(Item::NegativeArrayIndex)
+    } else {
+        index.parse().ok().map(Item::ArrayIndex)
+    }
+}
+
+// Parse a filter expression like `name == "tomli"` or `version =~ '^1\.'`
+fn parse_filter(expression: &str) -> Result<Option<Item>, TomliError> {
+    let trimmed = expression.trim_start();
+    // The key can either be quoted or consist of characters that are allowed in bare keys
+    let (key, rest) = match trimmed.chars().next() {
+        Some(quote @ ('"' | '\'')) => match trimmed[1..].split_once(quote) {
+            Some((key, rest)) => (key, rest),
+            None => return Ok(None),
+        },
+        _ => trimmed.split_at(
+            trimmed
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                .unwrap_or(trimmed.len()),
+        ),
+    };
+    if key.is_empty() {
+        return Ok(None);
+    }
+
+    let rest = rest.trim_start();
+    // Longer operators need to be checked first, since "<" is a prefix of "<="
+    let Some(operator) = ["==", "!=", "<=", ">=", "=~", "<", ">"]
+        .into_iter()
+        .find(|operator| rest.starts_with(operator))
+    else {
+        return Ok(None);
+    };
+    let Ok(value) = rest[operator.len()..].trim().parse::<Value>() else {
+        return Ok(None);
+    };
+
+    let operator = match operator {
+        "==" => Operator::Equal,
+        "!=" => Operator::NotEqual,
+        "<=" => Operator::LessThanOrEqual,
+        ">=" => Operator::GreaterThanOrEqual,
+        "<" => Operator::LessThan,
+        ">" => Operator::GreaterThan,
+        _ => match value.as_str() {
+            Some(pattern) => Operator::Matches(Regex::new(pattern)?),
+            // Regular expressions can only be given as strings
+            None => return Ok(None),
+        },
+    };
+
+    Ok(Some(Item::Filter(Box::new(Filter {
+        expression: expression.trim().to_string(),
+        key: key.to_string(),
+        operator,
+        value,
+    }))))
+}
+
+// Consume the tokens of a filter expression up to and including the closing bracket
+// The iterator is expected to point at the question mark that starts the filter
+fn parse_filter_tokens<'a>(
+    tokens_iterator: &mut Peekable<Iter<'a, Token>>,
+    counter: &mut usize,
+) -> Result<(Item, &'a Token), TomliError> {
+    let mut expression = String::new();
+    let mut quote: Option<char> = None;
+    // Skip the question mark
+    tokens_iterator.next();
+    *counter += 1;
+
+    loop {
+        *counter += 1;
+        match tokens_iterator.next() {
+            Some(token @ Token::CloseBracket) if quote.is_none() => {
+                let filter =
+                    parse_filter(&expression)?.ok_or(TomliError::QuerySyntaxError(*counter))?;
+                return Ok((filter, token));
+            }
+            Some(token) => {
+                let char = token.as_char();
+                if quote.is_none() && (char == '"' || char == '\'') {
+                    quote = Some(char);
+                } else if quote == Some(char) {
+                    quote = None;
+                }
+                expression.push(char);
+            }
+            None => return Err(TomliError::QuerySyntaxError(*counter)),
+        }
+    }
+}
+
+// Create the item for a key, depending on whether it was preceded by a recursive descent
+fn key_item(key: String, recursive: &mut bool) -> Item {
+    if std::mem::take(recursive) {
+        Item::RecursiveDescent(key)
+    } else {
+        Item::Key(key)
+    }
+}
+
 fn parse_tokens(tokens: Vec<Token>) -> Result<Vec<Item>, TomliError> {
     let mut tokens_iterator = tokens.iter().peekable();
     // TOML path that will be contructed depending on the tokens
@@ -53,18 +250,35 @@ fn parse_tokens(tokens: Vec<Token>) -> Result<Vec<Item>, TomliError> {
     let mut previous_token: Option<&Token> = None;
     let mut brackets_open = false;
     let mut quote: Option<char> = None;
+    // Whether the next key was preceded by two dots
+    let mut recursive = false;
 
     while let Some(next) = tokens_iterator.next() {
         match next {
+            // Everything inside of quotes is part of the key until the quote is closed again
+            // Inside of double quotes, a backslash escapes a double quote or another backslash
+            token if quote.is_some_and(|quote| token.as_char() != quote) => {
+                let escaped = tokens_iterator
+                    .next_if(|next| {
+                        quote == Some('"')
+                            && token.as_char() == '\\'
+                            && matches!(next.as_char(), '"' | '\\')
+                    })
+                    .map(Token::as_char);
+                if escaped.is_some() {
+                    counter += 1;
+                }
+                key.push(escaped.unwrap_or(token.as_char()));
+            }
             Token::Char(c) | Token::Number(c) => {
                 let char = *c;
                 // Depending on whether we are currently looking at an index or a key
                 // we need to append a different variable
                 let mut str_to_append = &mut key;
 
-                // if brackets are open and the current character is a number then we consider it
-                // and index
-                if brackets_open && matches!(next, Token::Number(_)) {
+                // if brackets are open and the current character is a number or a minus then we
+                // consider it an index
+                if brackets_open && (matches!(next, Token::Number(_)) || char == '-') {
                     str_to_append = &mut index;
                 // If a bracket was opened then it is not allowed to have characters until the
                 // bracket is closed again
@@ -73,8 +287,8 @@ fn parse_tokens(tokens: Vec<Token>) -> Result<Vec<Item>, TomliError> {
                     || ((char == '(' || char == ')' || char == ',') && quote.is_none())
                     // Quotes pair can only be started if placed at the beginning of a key or after a DOT
                     || ((char == '\'' || char == '"') && quote.is_none() && !matches!(previous_token, None | Some(&Token::Dot)))
-                    // Quotes pair can only be closed at the end or if a DOT is the next character
-                    || (quote.is_some() && quote == Some(char) && !matches!(tokens_iterator.peek(), Some(&Token::Dot) | None))
+                    // Quotes pair can only be closed at the end or if a DOT or an index is next
+                    || (quote.is_some() && quote == Some(char) && !matches!(tokens_iterator.peek(), Some(&Token::Dot) | Some(&Token::OpenBracket) | None))
                 {
                     return Err(TomliError::QuerySyntaxError(counter));
                 } else if quote.is_none() && (char == '\'' || char == '"') {
@@ -94,7 +308,7 @@ fn parse_tokens(tokens: Vec<Token>) -> Result<Vec<Item>, TomliError> {
                 // If we are at the end of the tokenstream, then we can push the last build key
                 // to the path
                 if tokens_iterator.peek().is_none() {
-                    path.push(Item::Key(key.clone()));
+                    path.push(key_item(key.clone(), &mut recursive));
                 }
             }
             Token::CloseBracket => {
@@ -111,34 +325,35 @@ fn parse_tokens(tokens: Vec<Token>) -> Result<Vec<Item>, TomliError> {
                 {
                     return Err(TomliError::QuerySyntaxError(counter));
                 }
-                if quote.is_none() {
-                    path.push(Item::ArrayIndex(
-                        index
-                            .parse()
-                            .expect("BUG: Expected a number but got something else"),
-                    ));
-                    brackets_open = false;
-                    index.clear();
-                } else {
-                    key.push(']');
+                // A wildcard or append has already been pushed to the path
+                if !matches!(previous_token, Some(&Token::Asterisk) | Some(&Token::Plus)) {
+                    path.push(parse_index(&index).ok_or(TomliError::QuerySyntaxError(counter))?);
                 }
+                brackets_open = false;
+                index.clear();
             }
             Token::Dot => {
+                // Two dots start a recursive descent, which must be followed by a key
+                let recursive_descent = matches!(previous_token, Some(&Token::Dot))
+                    && !recursive
+                    && matches!(
+                        tokens_iterator.peek(),
+                        Some(&Token::Char(_)) | Some(&Token::Number(_))
+                    );
                 // Sanity checks before doing anything
                 // A dot cannot be placed before a opening bracket
-                // A dot cannot be placed before a dot
+                // A dot cannot be placed before a dot, unless it starts a recursive descent
                 // A query cannot end with a "."
-                if matches!(
-                    previous_token,
-                    Some(&Token::OpenBracket) | Some(&Token::Dot)
-                ) || (previous_token.is_some() && tokens_iterator.peek().is_none())
+                if matches!(previous_token, Some(&Token::OpenBracket))
+                    || (matches!(previous_token, Some(&Token::Dot)) && !recursive_descent)
+                    || (previous_token.is_some() && tokens_iterator.peek().is_none())
                 {
                     return Err(TomliError::QuerySyntaxError(counter));
                 }
-                if quote.is_some() {
-                    key.push('.');
+                if recursive_descent {
+                    recursive = true;
                 } else if !key.is_empty() {
-                    path.push(Item::Key(key.clone()));
+                    path.push(key_item(key.clone(), &mut recursive));
                     key.clear();
                 }
             }
@@ -148,20 +363,77 @@ fn parse_tokens(tokens: Vec<Token>) -> Result<Vec<Item>, TomliError> {
                 // A query cannot end with a opening bracket
                 if !matches!(
                     previous_token,
-                    Some(&Token::Char(_)) | Some(&Token::CloseBracket)
+                    Some(&Token::Char(_)) | Some(&Token::CloseBracket) | Some(&Token::Asterisk)
                 ) || tokens_iterator.peek().is_none()
                 {
                     return Err(TomliError::QuerySyntaxError(counter));
                 }
-                if !key.is_empty() && quote.is_none() {
-                    path.push(Item::Key(key.clone()));
-                } else {
-                    key.push('[');
+                if !key.is_empty() {
+                    path.push(key_item(key.clone(), &mut recursive));
                 }
                 key.clear();
+
+                // A filter is parsed as a whole, including the closing bracket
+                if matches!(tokens_iterator.peek(), Some(&Token::QuestionMark)) {
+                    let (filter, close_bracket) =
+                        parse_filter_tokens(&mut tokens_iterator, &mut counter)?;
+                    // A closing bracket must be following by either a dot or another opening bracket
+                    if !matches!(
+                        tokens_iterator.peek(),
+                        Some(&Token::Dot) | Some(&Token::OpenBracket) | None
+                    ) {
+                        return Err(TomliError::QuerySyntaxError(counter));
+                    }
+                    path.push(filter);
+                    counter += 1;
+                    previous_token = Some(close_bracket);
+                    continue;
+                }
+
                 brackets_open = true;
             }
-            Token::Unkown(_) => return Err(TomliError::QuerySyntaxError(counter)),
+            Token::Asterisk => {
+                // A wildcard must fill a whole index (e.g. "[*]")
+                if brackets_open {
+                    if !matches!(previous_token, Some(&Token::OpenBracket))
+                        || !matches!(tokens_iterator.peek(), Some(&Token::CloseBracket))
+                    {
+                        return Err(TomliError::QuerySyntaxError(counter));
+                    }
+                    path.push(Item::Wildcard);
+                // A wildcard must fill a whole key (e.g. "table.*.key")
+                } else if matches!(previous_token, None | Some(&Token::Dot))
+                    && matches!(
+                        tokens_iterator.peek(),
+                        Some(&Token::Dot) | Some(&Token::OpenBracket) | None
+                    )
+                {
+                    path.push(Item::Wildcard);
+                } else {
+                    return Err(TomliError::QuerySyntaxError(counter));
+                }
+            }
+            Token::Colon => {
+                if brackets_open {
+                    index.push(':');
+                } else {
+                    return Err(TomliError::QuerySyntaxError(counter));
+                }
+            }
+            Token::Plus => {
+                // An append must fill a whole index (e.g. "[+]")
+                if brackets_open
+                    && matches!(previous_token, Some(&Token::OpenBracket))
+                    && matches!(tokens_iterator.peek(), Some(&Token::CloseBracket))
+                {
+                    path.push(Item::Append);
+                } else {
+                    return Err(TomliError::QuerySyntaxError(counter));
+                }
+            }
+            Token::QuestionMark | Token::Unkown(_) => {
+                return Err(TomliError::QuerySyntaxError(counter));
+            }
         }
         counter += 1;
         previous_token = Some(next);
@@ -174,6 +446,42 @@ fn parse_tokens(tokens: Vec<Token>) -> Result<Vec<Item>, TomliError> {
     }
 }
 
+/// Convert a concrete path back into a query that selects the same item
+pub fn to_query(path: &[Item]) -> String {
+    let mut query = String::new();
+    for segment in path {
+        match segment {
+            Item::Key(key) => {
+                if !query.is_empty() {
+                    query.push('.');
+                }
+                // Keys that don't consist of bare key characters need to be quoted
+                // Single quotes take everything literally, so they are preferred if possible
+                if !key.is_empty()
+                    && key
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+                {
+                    query.push_str(key);
+                } else if (key.contains('"') || key.contains('\\')) && !key.contains('\'') {
+                    query.push_str(&format!("'{key}'"));
+                } else {
+                    let key = key.replace('\\', "\\\\").replace('"', "\\\"");
+                    query.push_str(&format!("\"{key}\""));
+                }
+            }
+            Item::ArrayIndex(index) => query.push_str(&format!("[{index}]")),
+            _ => unreachable!("BUG: Only concrete paths can be converted into a query"),
+        }
+    }
+
+    if query.is_empty() {
+        String::from(".")
+    } else {
+        query
+    }
+}
+
 /// Evaluate `query` and generate a path to the requested TOML item
 /// If query is none or "" or ".", then an empty vec is returned
 pub fn evaluate(query: &str) -> Result<Vec<Item>, TomliError> {
@@ -189,7 +497,7 @@ pub fn evaluate(query: &str) -> Result<Vec<Item>, TomliError> {
 
 #[cfg(test)]
 mod tests {
-    use crate::parser::evaluate;
+    use crate::parser::{Item, evaluate, to_query};
 
     #[test]
     fn test_valid_quries() {
@@ -218,6 +526,40 @@ mod tests {
             "table.\"'key\"",
             "table.'key'",
             "table.'\"key'",
+            "*",
+            "table.*",
+            "table.*.key",
+            "table[*]",
+            "table[*].key",
+            "table.*[*]",
+            "table.\"*\"",
+            "table[-1]",
+            "table[-10].key",
+            "table[1:3]",
+            "table[:-1]",
+            "table[-2:]",
+            "table[:]",
+            "table[1:3][0]",
+            "table.\"key:value\"",
+            "..key",
+            "table..key",
+            "..table..key",
+            "..key[0]",
+            "..key.key",
+            "..'key'",
+            "table[?key == \"value\"]",
+            "table[?key==1].key",
+            "table[?key != 'value'][0]",
+            "table[?key < 1.5]",
+            "table[?key >= 2]",
+            "table[?key =~ \"^val\"]",
+            "table[?'key]' == \"]\"]",
+            "table[?key == \"a]b\"].key",
+            "table[?key == true][?key == false]",
+            "table[+]",
+            "table[+].key",
+            "table[+][+]",
+            "table.\"a+b\"",
         ];
 
         for query in queries {
@@ -255,6 +597,40 @@ mod tests {
             "table(",
             "table)",
             "table.('key')",
+            "table*",
+            "table.*key",
+            "table.**",
+            "table[*1]",
+            "table[1*]",
+            "table[*",
+            "table[-]",
+            "table[-0]",
+            "table[--1]",
+            "table[1-]",
+            "table[1:2:3]",
+            "table[1:-]",
+            "table:key",
+            "table[:",
+            "...key",
+            "table..",
+            "table..[0]",
+            "table..*",
+            "table[?]",
+            "table[?key]",
+            "table[?key ==]",
+            "table[?key == value]",
+            "table[?== 1]",
+            "table[?key <> 1]",
+            "table[?key =~ 1]",
+            "table[?key =~ \"(\"]",
+            "table[?key == 1",
+            "table[?key == 1]key",
+            "table?",
+            "table+",
+            "table[+1]",
+            "table[1+]",
+            "table.+",
+            "table[+",
         ];
 
         for query in queries {
@@ -262,4 +638,52 @@ mod tests {
             assert!(evaluate(query).is_err());
         }
     }
+
+    #[test]
+    fn test_to_query() {
+        let queries = vec![
+            ".",
+            "table",
+            "table.key",
+            "table[0].key",
+            "table.\"key with spaces\"",
+            "table.'\"quoted\"'",
+            "table.\"*\"",
+            "array[1][2]",
+        ];
+
+        for query in queries {
+            println!("Testing {}", query);
+            assert_eq!(to_query(&evaluate(query).unwrap()), query);
+        }
+    }
+
+    #[test]
+    fn test_to_query_round_trip() {
+        let keys = vec![
+            "a@b",
+            "a.b",
+            "a..b",
+            "[0]",
+            "a]b",
+            "*",
+            "?",
+            "x:y",
+            "'single'",
+            "\"double\"",
+            "'both\"",
+            "C:\\path\\",
+            "\\\"",
+            "ü ß",
+        ];
+
+        for key in keys {
+            println!("Testing {}", key);
+            let path = vec![Item::Key(key.to_string()), Item::ArrayIndex(1)];
+            let evaluated = evaluate(&to_query(&path)).unwrap();
+            assert!(
+                matches!(evaluated.as_slice(), [Item::Key(evaluated_key), Item::ArrayIndex(1)] if evaluated_key == key)
+            );
+        }
+    }
 }
@@ -0,0 +1,185 @@
+use serde_json::Value as JsonValue;
+use toml_edit::{DocumentMut, Item};
+
+use crate::errors::TomliError;
+use crate::{delete, json, move_item, parser, query, set};
+
+// Translate a JSON pointer (RFC 6901) into a path
+// Whether a token is a key or an array index depends on the item it is used on
+fn parse_pointer(pointer: &str, root: &Item) -> Result<Vec<parser::Item>, TomliError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(pointer) = pointer.strip_prefix('/') else {
+        return Err(TomliError::InvalidPatch(
+            "JSON pointers must start with \"/\"",
+        ));
+    };
+
+    let mut path = Vec::new();
+    let mut item = Some(root);
+    for token in pointer.split('/') {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        let segment = match item {
+            Some(array) if query::array_len(array).is_some() => match token.as_str() {
+                "-" => parser::Item::Append,
+                // Leading zeros are not allowed in array indices
+                index if index == "0" || !index.starts_with('0') => {
+                    index.parse().map(parser::Item::ArrayIndex).map_err(|_| {
+                        TomliError::InvalidPatch("Arrays can only be accessed by index")
+                    })?
+                }
+                _ => {
+                    return Err(TomliError::InvalidPatch(
+                        "Arrays can only be accessed by index",
+                    ));
+                }
+            },
+            _ => parser::Item::Key(token),
+        };
+        item =
+            item.and_then(|item| query::parse_toml_path(std::slice::from_ref(&segment), item).ok());
+        path.push(segment);
+    }
+
+    Ok(path)
+}
+
+// Remove the item at `path` and return it
+fn remove(document: &mut DocumentMut, path: Vec<parser::Item>) -> Result<Item, TomliError> {
+    if path.is_empty() {
+        return Err(TomliError::InvalidPatch(
+            "Removing the document as a whole is not supported",
+        ));
+    }
+    // Make sure that the item exists, since deleting it would create missing parents
+    query::parse_toml_path(&path, document.as_item())?;
+    delete::delete_item(document, path)
+}
+
+// Set `item` at `path`, in insert mode elements in arrays are inserted instead of replaced
+fn add(
+    document: &mut DocumentMut,
+    path: &[parser::Item],
+    item: Item,
+    insert: bool,
+) -> Result<(), TomliError> {
+    let Some((last, parent_path)) = path.split_last() else {
+        // An empty path replaces the whole document
+        return match item {
+            Item::Table(table) => {
+                *document.as_table_mut() = table;
+                Ok(())
+            }
+            _ => Err(TomliError::InvalidPatch(
+                "The document can only be replaced by an object",
+            )),
+        };
+    };
+
+    // The parent has to exist, only the last item is created
+    let parent = query::parse_toml_path(parent_path, document.as_item())?;
+    let item = if parent.is_value() {
+        Item::Value(
+            item.into_value()
+                .map_err(|_| TomliError::InvalidPatch("This value can not be added to an array"))?,
+        )
+    } else if parent.is_array_of_tables() {
+        Item::Table(item.into_table().map_err(|_| {
+            TomliError::InvalidPatch("Only objects can be added to an array of tables")
+        })?)
+    } else {
+        item
+    };
+
+    let insert = insert && matches!(last, parser::Item::ArrayIndex(_));
+    let decor = set::existing_decor(path, document.as_item()).filter(|_| !insert);
+    let target = set::parse_toml_path(path, document.as_item_mut(), false, insert)?;
+    // Inserted elements keep the decor of their placeholder to match their neighbours
+    // New keys get the default decor, so that comments of a copied value are not duplicated
+    let decor = if insert {
+        target.as_value().map(|value| value.decor().clone())
+    } else {
+        Some(decor.unwrap_or_default())
+    };
+    set::replace_item(target, item, decor);
+
+    Ok(())
+}
+
+fn get_str<'a>(operation: &'a JsonValue, field: &'static str) -> Result<&'a str, TomliError> {
+    operation
+        .get(field)
+        .and_then(JsonValue::as_str)
+        .ok_or(TomliError::PatchMissingField(field))
+}
+
+fn get_value(operation: &JsonValue) -> Result<&JsonValue, TomliError> {
+    operation
+        .get("value")
+        .ok_or(TomliError::PatchMissingField("value"))
+}
+
+fn apply(document: &mut DocumentMut, operation: &JsonValue) -> Result<(), TomliError> {
+    let path = parse_pointer(get_str(operation, "path")?, document.as_item())?;
+
+    match get_str(operation, "op")? {
+        "add" => add(document, &path, json::to_item(get_value(operation)?)?, true),
+        "remove" => remove(document, path).map(|_| ()),
+        "replace" => {
+            // The value that is replaced has to exist
+            query::parse_toml_path(&path, document.as_item())?;
+            add(
+                document,
+                &path,
+                json::to_item(get_value(operation)?)?,
+                false,
+            )
+        }
+        "move" => {
+            let from = parse_pointer(get_str(operation, "from")?, document.as_item())?;
+            if path.len() > from.len() && move_item::same_path(&from, &path[..from.len()]) {
+                return Err(TomliError::InvalidPatch(
+                    "An item can not be moved into one of its children",
+                ));
+            }
+            let mut item = remove(document, from)?;
+            move_item::reset_position(&mut item);
+            // The item was removed, so the path has to be resolved again
+            let path = parse_pointer(get_str(operation, "path")?, document.as_item())?;
+            add(document, &path, item, true)
+        }
+        "copy" => {
+            let from = parse_pointer(get_str(operation, "from")?, document.as_item())?;
+            let mut item = query::parse_toml_path(&from, document.as_item())?.clone();
+            move_item::reset_position(&mut item);
+            add(document, &path, item, true)
+        }
+        "test" => {
+            let item = query::parse_toml_path(&path, document.as_item())?;
+            if json::from_item(item) != *get_value(operation)? {
+                return Err(TomliError::PatchTestFailed(
+                    get_str(operation, "path")?.to_string(),
+                ));
+            }
+            Ok(())
+        }
+        _ => Err(TomliError::InvalidPatch("Unknown operation")),
+    }
+}
+
+pub fn exec(document: &mut DocumentMut, patch: &str) -> Result<String, TomliError> {
+    let patch: JsonValue = serde_json::from_str(patch)?;
+    let Some(operations) = patch.as_array() else {
+        return Err(TomliError::InvalidPatch(
+            "A patch has to be an array of operations",
+        ));
+    };
+
+    for (index, operation) in operations.iter().enumerate() {
+        apply(document, operation)
+            .map_err(|error| TomliError::PatchOperationFailed(index, Box::new(error)))?;
+    }
+
+    Ok(document.to_string())
+}
@@ -1,10 +1,14 @@
+use std::cmp::Ordering;
 use std::str;
-use toml_edit::{DocumentMut, Item};
+use toml_edit::{Date, Datetime, DocumentMut, Item, Offset, Time, Value};
 
 use crate::errors::TomliError;
-use crate::parser;
+use crate::{Format, json, parser, yaml};
 
-pub(crate) fn parse_toml_path(path: Vec<parser::Item>, root: &Item) -> Result<&Item, TomliError> {
+pub(crate) fn parse_toml_path<'a>(
+    path: &[parser::Item],
+    root: &'a Item,
+) -> Result<&'a Item, TomliError> {
     let mut item = root;
     for segment in path.iter() {
         match segment {
@@ -26,17 +30,303 @@ pub(crate) fn parse_toml_path(path: Vec<parser::Item>, root: &Item) -> Result<&I
                     return Err(TomliError::InvalidKeyAccess(index.to_string()));
                 }
             }
+            parser::Item::Append => return Err(TomliError::AppendNotAllowed),
+            // Selectors depend on the document and need to be expanded first
+            _ => unreachable!("BUG: Selectors must be expanded before resolving a path"),
         };
     }
 
     Ok(item)
 }
 
-pub fn exec(document: &DocumentMut, query: &str) -> Result<String, TomliError> {
+// Return all direct children of `item` together with the path segment that leads to them
+pub(crate) fn children(item: &Item) -> Vec<(parser::Item, &Item)> {
+    if let Some(table) = item.as_table_like() {
+        table
+            .iter()
+            .map(|(key, child)| (parser::Item::Key(key.to_string()), child))
+            .collect()
+    } else {
+        (0..)
+            .map_while(|index| item.get(index).map(|child| (index, child)))
+            .map(|(index, child)| (parser::Item::ArrayIndex(index), child))
+            .collect()
+    }
+}
+
+// Return all items below `item` in depth-first order together with their path relative to `item`
+fn descendants(item: &Item) -> Vec<(Vec<parser::Item>, &Item)> {
+    let mut result = Vec::new();
+    for (segment, child) in children(item) {
+        result.push((vec![segment.clone()], child));
+        for (mut path, descendant) in descendants(child) {
+            path.insert(0, segment.clone());
+            result.push((path, descendant));
+        }
+    }
+
+    result
+}
+
+// Return the number of elements if `item` is an array or an array of tables
+pub(crate) fn array_len(item: &Item) -> Option<usize> {
+    item.as_array()
+        .map(|array| array.len())
+        .or_else(|| item.as_array_of_tables().map(|array| array.len()))
+}
+
+// Number of days since 1970-01-01 in the proleptic Gregorian calendar
+fn days_since_epoch(date: &Date) -> i64 {
+    let (month, day) = (i64::from(date.month), i64::from(date.day));
+    // The year starts in March, so that the leap day is the last day of the year
+    let year = i64::from(date.year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// Seconds and nanoseconds of a time, omitted seconds are zero
+fn time_of_day(time: &Time) -> (i64, u32) {
+    let seconds = i64::from(time.hour) * 3600
+        + i64::from(time.minute) * 60
+        + i64::from(time.second.unwrap_or_default());
+    (seconds, time.nanosecond.unwrap_or_default())
+}
+
+// Compare two datetimes of the same flavor (e.g. two local dates)
+// Offset datetimes are compared by the instant they represent
+fn compare_datetime(left: &Datetime, right: &Datetime) -> Option<Ordering> {
+    if left.date.is_some() != right.date.is_some()
+        || left.time.is_some() != right.time.is_some()
+        || left.offset.is_some() != right.offset.is_some()
+    {
+        return None;
+    }
+
+    let key = |datetime: &Datetime| {
+        let offset = match datetime.offset {
+            Some(Offset::Custom { minutes }) => i64::from(minutes) * 60,
+            _ => 0,
+        };
+        let days = datetime
+            .date
+            .as_ref()
+            .map(days_since_epoch)
+            .unwrap_or_default();
+        let (seconds, nanoseconds) = datetime.time.as_ref().map(time_of_day).unwrap_or_default();
+        (days * 86400 + seconds - offset, nanoseconds)
+    };
+    Some(key(left).cmp(&key(right)))
+}
+
+// Compare two values of the same type, integers and floats can be compared with each other
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::String(left), Value::String(right)) => Some(left.value().cmp(right.value())),
+        (Value::Integer(left), Value::Integer(right)) => Some(left.value().cmp(right.value())),
+        (Value::Float(left), Value::Float(right)) => left.value().partial_cmp(right.value()),
+        (Value::Integer(left), Value::Float(right)) => {
+            (*left.value() as f64).partial_cmp(right.value())
+        }
+        (Value::Float(left), Value::Integer(right)) => {
+            left.value().partial_cmp(&(*right.value() as f64))
+        }
+        (Value::Boolean(left), Value::Boolean(right)) => Some(left.value().cmp(right.value())),
+        (Value::Datetime(left), Value::Datetime(right)) => {
+            compare_datetime(left.value(), right.value())
+        }
+        _ => None,
+    }
+}
+
+// Check whether `item` is a table that contains a value which fulfills the filter condition
+fn matches_filter(filter: &parser::Filter, item: &Item) -> bool {
+    let Some(value) = item.get(&filter.key).and_then(Item::as_value) else {
+        return false;
+    };
+
+    let ordering = compare(value, &filter.value);
+    match &filter.operator {
+        parser::Operator::Equal => ordering.is_some_and(Ordering::is_eq),
+        parser::Operator::NotEqual => ordering.is_some_and(Ordering::is_ne),
+        parser::Operator::LessThan => ordering.is_some_and(Ordering::is_lt),
+        parser::Operator::LessThanOrEqual => ordering.is_some_and(Ordering::is_le),
+        parser::Operator::GreaterThan => ordering.is_some_and(Ordering::is_gt),
+        parser::Operator::GreaterThanOrEqual => ordering.is_some_and(Ordering::is_ge),
+        parser::Operator::Matches(regex) => {
+            value.as_str().is_some_and(|value| regex.is_match(value))
+        }
+    }
+}
+
+// Resolve a single selector against `item` and return the items that it matches together with
+// their path relative to `item`
+fn resolve_selector<'a>(
+    segment: &parser::Item,
+    item: &'a Item,
+) -> Result<Vec<(Vec<parser::Item>, &'a Item)>, TomliError> {
+    let matches = match segment {
+        parser::Item::RecursiveDescent(key) => {
+            return Ok(descendants(item)
+                .into_iter()
+                .filter(
+                    |(path, _)| matches!(path.last(), Some(parser::Item::Key(last)) if last == key),
+                )
+                .collect());
+        }
+        parser::Item::Wildcard => {
+            if !(item.is_table_like() || item.is_array() || item.is_array_of_tables()) {
+                return Err(TomliError::InvalidKeyAccess(String::from("*")));
+            }
+
+            children(item)
+        }
+        parser::Item::NegativeArrayIndex(index) => {
+            let len = array_len(item).ok_or(TomliError::InvalidKeyAccess(format!("-{index}")))?;
+            if *index > len {
+                return Err(TomliError::NegativeIndexOutOfBounds(*index));
+            }
+
+            let index = len - index;
+            let child = item
+                .get(index)
+                .expect("BUG: Expected item at index but could not find it");
+
+            vec![(parser::Item::ArrayIndex(index), child)]
+        }
+        parser::Item::Slice(start, end) => {
+            let len = array_len(item).ok_or(TomliError::InvalidKeyAccess(format!(
+                "{}:{}",
+                start.map(|start| start.to_string()).unwrap_or_default(),
+                end.map(|end| end.to_string()).unwrap_or_default(),
+            )))?;
+            // Same semantics as slices in Python: negative bounds count from the end and
+            // bounds that are out of range are clamped to the array
+            let clamp = |bound: isize| {
+                if bound < 0 {
+                    len.saturating_sub(bound.unsigned_abs())
+                } else {
+                    len.min(bound.unsigned_abs())
+                }
+            };
+            let start = start.map(clamp).unwrap_or(0);
+            let end = end.map(clamp).unwrap_or(len);
+
+            children(item).into_iter().take(end).skip(start).collect()
+        }
+        parser::Item::Filter(filter) => {
+            if array_len(item).is_none() {
+                return Err(TomliError::InvalidKeyAccess(format!(
+                    "?{}",
+                    filter.expression
+                )));
+            }
+
+            children(item)
+                .into_iter()
+                .filter(|(_, child)| matches_filter(filter, child))
+                .collect()
+        }
+        _ => vec![(
+            segment.clone(),
+            parse_toml_path(std::slice::from_ref(segment), item)?,
+        )],
+    };
+
+    Ok(matches
+        .into_iter()
+        .map(|(segment, child)| (vec![segment], child))
+        .collect())
+}
+
+/// Resolve all selectors (e.g. wildcards) in `path` against `root` and return every
+/// concrete path that is matched
+///
+/// Once a selector that can match multiple items was used, branches that do not contain the
+/// remaining segments are skipped instead of returning an error.
+pub(crate) fn expand_toml_path(
+    path: &[parser::Item],
+    root: &Item,
+) -> Result<Vec<Vec<parser::Item>>, TomliError> {
+    let mut branches = vec![(Vec::new(), root)];
+    let mut fan_out = false;
+
+    for segment in path.iter() {
+        let mut next_branches = Vec::new();
+
+        for (prefix, item) in branches {
+            let matches = match resolve_selector(segment, item) {
+                Ok(matches) => matches,
+                Err(error) if !fan_out => return Err(error),
+                Err(_) => Vec::new(),
+            };
+
+            for (path, child) in matches {
+                let mut concrete_path = prefix.clone();
+                concrete_path.extend(path);
+                next_branches.push((concrete_path, child));
+            }
+        }
+
+        fan_out |= segment.matches_multiple();
+        branches = next_branches;
+    }
+
+    Ok(branches.into_iter().map(|(path, _)| path).collect())
+}
+
+// Render a value without its decor and strings without quotes or escape sequences
+fn raw_value(value: &Value) -> String {
+    if let Value::String(string) = value {
+        string.value().clone()
+    } else {
+        let mut value = value.clone();
+        value.decor_mut().clear();
+        value.to_string()
+    }
+}
+
+// Render a query result in the requested output format
+fn render(item: &Item, raw: bool, output_format: &Format, compact: bool) -> String {
+    match output_format {
+        Format::Toml => match item.as_value() {
+            // The elements of an array are printed one per line
+            Some(Value::Array(array)) if raw => {
+                array.iter().map(raw_value).collect::<Vec<_>>().join("\n")
+            }
+            Some(value) if raw => raw_value(value),
+            _ => item.to_string(),
+        },
+        Format::Json | Format::Yaml => match json::from_item(item) {
+            serde_json::Value::String(string) if raw => string,
+            value if matches!(output_format, Format::Yaml) => yaml::to_string(&value),
+            value => json::to_string(&value, compact),
+        },
+    }
+}
+
+pub fn exec(
+    document: &DocumentMut,
+    query: &str,
+    raw: bool,
+    output_format: Format,
+    compact: bool,
+) -> Result<String, TomliError> {
     // An empty query or a dot are evaluated as the whole document
     if query == "." || query.is_empty() {
-        return Ok(document.to_string());
+        return Ok(match output_format {
+            Format::Toml => document.to_string(),
+            _ => render(document.as_item(), raw, &output_format, compact),
+        });
+    }
+
+    let mut results = Vec::new();
+    for path in expand_toml_path(&parser::evaluate(query)?, document.as_item())? {
+        let item = parse_toml_path(&path, document.as_item())?;
+        results.push(render(item, raw, &output_format, compact));
     }
 
-    Ok(parse_toml_path(parser::evaluate(query)?, document.as_item())?.to_string())
+    Ok(results.join("\n"))
 }
@@ -1,21 +1,121 @@
+use clap::ValueEnum;
 use std::str;
 use std::str::FromStr;
-use toml_edit::{Array, Datetime, DocumentMut, InlineTable, Item, Table, Value};
+use toml_edit::{
+    Array, ArrayOfTables, Datetime, Decor, DocumentMut, InlineTable, Item, Table, Value,
+};
+use toml_writer::{ToTomlValue, TomlStringBuilder};
 
 use crate::errors::TomliError;
-use crate::{ValueType, parser};
+use crate::{IntFormat, StringStyle, ValueType, move_item, parser, query};
 
-pub(crate) fn parse_toml_path(
-    path: Vec<parser::Item>,
-    root: &mut Item,
+// Keep only the whitespace of a decor, so that comments are not duplicated when it is copied
+// Comments end at a line break, so only the indentation after the last one is whitespace
+fn whitespace_decor(decor: &Decor) -> Decor {
+    let prefix = decor
+        .prefix()
+        .and_then(|prefix| prefix.as_str())
+        .unwrap_or(" ");
+    let prefix = match prefix.rfind('\n') {
+        Some(position) => format!("\n{}", &prefix[position + 1..]),
+        None if prefix.trim().is_empty() => prefix.to_string(),
+        None => String::from(" "),
+    };
+    let suffix = decor
+        .suffix()
+        .and_then(|suffix| suffix.as_str())
+        .filter(|suffix| suffix.trim().is_empty())
+        .unwrap_or("");
+    Decor::new(prefix, suffix)
+}
+
+// Keep only the empty lines in front of a table header and the whitespace after it
+fn whitespace_table_decor(decor: &Decor) -> Decor {
+    let mut whitespace = Decor::default();
+    if let Some(prefix) = decor.prefix().and_then(|prefix| prefix.as_str()) {
+        whitespace.set_prefix(
+            prefix
+                .split_inclusive('\n')
+                .filter(|line| line.trim().is_empty())
+                .collect::<String>(),
+        );
+    }
+    if let Some(suffix) = decor
+        .suffix()
+        .and_then(|suffix| suffix.as_str())
+        .filter(|suffix| suffix.trim().is_empty())
+    {
+        whitespace.set_suffix(suffix);
+    }
+    whitespace
+}
+
+// The decor of an element that is not the first element of the array
+fn following_decor(array: &Array) -> Decor {
+    array
+        .get(1)
+        // A multiline array with a single element uses the same indentation for every element
+        .or_else(|| {
+            array.get(0).filter(|first| {
+                first
+                    .decor()
+                    .prefix()
+                    .and_then(|prefix| prefix.as_str())
+                    .is_some_and(|prefix| prefix.contains('\n'))
+            })
+        })
+        .map(|value| whitespace_decor(value.decor()))
+        .unwrap_or(Decor::new(" ", ""))
+}
+
+// Insert a placeholder at `index` while keeping the formatting of the surrounding elements
+// The placeholder takes over the decor of the element that was at its position before, which then
+// only keeps its whitespace, so that comments stay at their position
+pub(crate) fn insert_placeholder(array: &mut Array, index: usize) {
+    let decor = match array.get(index) {
+        Some(value) => value.decor().clone(),
+        None if array.is_empty() => Decor::new("", ""),
+        // Appending to an array with at least two elements, so we can copy the decor of the
+        // last element without copying the decor of the first element
+        None if array.len() > 1 => array
+            .get(array.len() - 1)
+            .map(|value| whitespace_decor(value.decor()))
+            .unwrap_or_else(|| following_decor(array)),
+        None => following_decor(array),
+    };
+    // The element that was first before needs the decor of a non-first element now
+    let shifted_decor = match index {
+        0 if !array.is_empty() => Some(following_decor(array)),
+        _ => array
+            .get(index)
+            .map(|value| whitespace_decor(value.decor())),
+    };
+    if let (Some(shifted_decor), Some(shifted)) = (shifted_decor, array.get_mut(index)) {
+        *shifted.decor_mut() = shifted_decor;
+    }
+
+    let mut placeholder = Value::from("");
+    *placeholder.decor_mut() = decor;
+    array.insert_formatted(index, placeholder);
+}
+
+pub(crate) fn parse_toml_path<'a>(
+    path: &[parser::Item],
+    root: &'a mut Item,
     dotted_key: bool,
-) -> Result<&mut Item, TomliError> {
+    insert: bool,
+) -> Result<&'a mut Item, TomliError> {
     let mut item = root;
     // When parsing the toml path we want to keep track of whether we are currently in a inlined
     // table or not. Depending on this, we either crate a new table or a new inlined table.
     let mut inline_table = false;
+    // In insert mode, a new element is inserted at the last index instead of overwriting it
+    let insert_position = path
+        .iter()
+        .rposition(|segment| matches!(segment, parser::Item::ArrayIndex(_)))
+        .filter(|_| insert);
 
-    for segment in path.iter() {
+    for (position, segment) in path.iter().enumerate() {
         match segment {
             parser::Item::Key(key) => {
                 item = match item {
@@ -63,10 +163,41 @@ pub(crate) fn parse_toml_path(
             }
             parser::Item::ArrayIndex(index) => {
                 item = match item {
+                    Item::ArrayOfTables(array) if insert_position == Some(position) => {
+                        if *index > array.len() {
+                            return Err(TomliError::IndexOutOfBounds(*index));
+                        }
+                        let mut tables = array.iter().cloned().collect::<Vec<_>>();
+                        // Like for value arrays, the new table takes over the decor of the table
+                        // at its position, which gets the whitespace of a non-first table
+                        let mut table = Table::new();
+                        if *index < tables.len() {
+                            let shifted_decor = tables
+                                .get((*index).max(1))
+                                .map(|table| whitespace_table_decor(table.decor()))
+                                .unwrap_or_default();
+                            *table.decor_mut() =
+                                std::mem::replace(tables[*index].decor_mut(), shifted_decor);
+                        }
+                        tables.insert(*index, table);
+                        *array = tables.into_iter().collect();
+
+                        item.get_mut(index)
+                            .expect("BUG: Expected item at index but could not find it")
+                    }
+                    Item::Value(Value::Array(array)) if insert_position == Some(position) => {
+                        if *index > array.len() {
+                            return Err(TomliError::IndexOutOfBounds(*index));
+                        }
+                        insert_placeholder(array, *index);
+                        inline_table = true;
+
+                        item.get_mut(index)
+                            .expect("BUG: Expected item at index but could not find it")
+                    }
                     Item::ArrayOfTables(_) | Item::Value(Value::Array(_)) => {
                         // Either return the item at index "index" or show an out of bounds message
-                        // TODO: Maybe we want to replace the out of bounds message with just
-                        // appending the item to the end of the array
+                        // Appending an item is done with the append syntax ("[+]")
                         if item.get(*index).is_none() {
                             return Err(TomliError::IndexOutOfBounds(*index));
                         }
@@ -84,19 +215,390 @@ pub(crate) fn parse_toml_path(
                     }
                 };
             }
+            parser::Item::Append => {
+                item = match item {
+                    // Push a new table and continue with it
+                    Item::ArrayOfTables(array) => {
+                        array.push(Table::new());
+                        let index = array.len() - 1;
+                        item.get_mut(index)
+                            .expect("BUG: Expected item at index but could not find it")
+                    }
+                    // Push a placeholder that will either be overwritten by the value or
+                    // converted into an inline table by the next segment
+                    Item::Value(Value::Array(array)) => {
+                        let index = array.len();
+                        insert_placeholder(array, index);
+                        inline_table = true;
+                        item.get_mut(index)
+                            .expect("BUG: Expected item at index but could not find it")
+                    }
+                    // Create a new array with a single element
+                    // If a key follows and we are not in an inline table, then the user most
+                    // likely wants to add a new table (e.g. "bin[+].name")
+                    _ => {
+                        if !inline_table
+                            && matches!(path.get(position + 1), Some(parser::Item::Key(_)))
+                        {
+                            let mut array = ArrayOfTables::new();
+                            array.push(Table::new());
+                            *item = Item::ArrayOfTables(array);
+                        } else {
+                            let mut array = Array::new();
+                            array.push("");
+                            *item = Item::Value(Value::Array(array));
+                            inline_table = true;
+                        }
+
+                        item.get_mut(0)
+                            .expect("BUG: Expected item at index but could not find it")
+                    }
+                };
+            }
+            // Selectors depend on the document and need to be expanded first
+            _ => unreachable!("BUG: Selectors must be expanded before resolving a path"),
         };
     }
 
     Ok(item)
 }
 
+// Check if `path` can be resolved below `item` without going through a scalar value
+fn can_descend(path: &[parser::Item], mut item: &Item) -> bool {
+    for segment in path {
+        let child = match segment {
+            parser::Item::Key(key) if item.is_table_like() => item.get(key),
+            parser::Item::ArrayIndex(index) if query::array_len(item).is_some() => item.get(*index),
+            parser::Item::Append if query::array_len(item).is_some() => None,
+            _ => return false,
+        };
+        // Missing items are created by `parse_toml_path`
+        let Some(child) = child else {
+            return true;
+        };
+        item = child;
+    }
+    true
+}
+
+/// Expand the selectors in `path` against `root`
+///
+/// Segments after the last selector are kept as they are, so that they can be created
+/// by `parse_toml_path` if they don't exist yet.
+/// Like in `query::expand_toml_path`, matches of a selector that can match multiple items are
+/// skipped if the remaining segments would have to go through a scalar value.
+pub(crate) fn expand_toml_path(
+    mut path: Vec<parser::Item>,
+    root: &Item,
+) -> Result<Vec<Vec<parser::Item>>, TomliError> {
+    let Some(last_selector) = path.iter().rposition(parser::Item::is_selector) else {
+        return Ok(vec![path]);
+    };
+    let remaining_path = path.split_off(last_selector + 1);
+    let fan_out = path.iter().any(parser::Item::matches_multiple);
+
+    let paths: Vec<Vec<parser::Item>> = query::expand_toml_path(&path, root)?
+        .into_iter()
+        .filter(|concrete_path| {
+            !fan_out
+                || query::parse_toml_path(concrete_path, root)
+                    .is_ok_and(|item| can_descend(&remaining_path, item))
+        })
+        .map(|mut concrete_path| {
+            concrete_path.extend(remaining_path.iter().cloned());
+            concrete_path
+        })
+        .collect();
+
+    // A match inside of another match (e.g. "..a" on "a = { a = 1 }") would be applied to an
+    // item that was already replaced, so only the outermost match is kept
+    Ok(paths
+        .iter()
+        .filter(|concrete_path| {
+            !paths.iter().any(|other| {
+                other.len() < concrete_path.len()
+                    && move_item::same_path(other, &concrete_path[..other.len()])
+            })
+        })
+        .cloned()
+        .collect())
+}
+
+// Convert the given string into an item of the requested value type
+fn parse_value(
+    value: &str,
+    value_type: &ValueType,
+    string_style: &StringStyle,
+) -> Result<Item, TomliError> {
+    Ok(match value_type {
+        ValueType::Str => styled_string(value, string_style)?,
+        // Use the TOML rules for numbers, so that the radix and underscores are kept
+        ValueType::Int => match Value::from_str(value) {
+            Ok(mut value) if value.is_integer() => {
+                value.decor_mut().clear();
+                Item::Value(value)
+            }
+            _ => toml_edit::value::<i64>(i64::from_str(value)?),
+        },
+        ValueType::Float => match Value::from_str(value) {
+            Ok(mut value) if value.is_float() => {
+                value.decor_mut().clear();
+                Item::Value(value)
+            }
+            _ => toml_edit::value::<f64>(f64::from_str(value)?),
+        },
+        ValueType::Bool => toml_edit::value::<bool>(bool::from_str(value)?),
+        ValueType::Datetime => toml_edit::value::<Datetime>(Datetime::from_str(value)?),
+        ValueType::Date
+        | ValueType::Time
+        | ValueType::LocalDatetime
+        | ValueType::OffsetDatetime => toml_edit::value(parse_datetime(value, value_type)?),
+        // Follow the TOML rules for literals and fall back to a string if nothing matches
+        ValueType::Auto => match Value::from_str(value) {
+            Ok(mut value)
+                if value.is_bool()
+                    || value.is_integer()
+                    || value.is_float()
+                    || value.is_datetime() =>
+            {
+                value.decor_mut().clear();
+                Item::Value(value)
+            }
+            _ => styled_string(value, string_style)?,
+        },
+        ValueType::Toml => {
+            let mut value = Value::from_str(value).map_err(TomliError::ValueToTomlError)?;
+            // The surrounding whitespace is determined by the position of the value
+            value.decor_mut().clear();
+            Item::Value(value)
+        }
+    })
+}
+
+// Create a string value that is quoted with the requested style
+fn styled_string(value: &str, string_style: &StringStyle) -> Result<Item, TomliError> {
+    let builder = TomlStringBuilder::new(value);
+    let string = match string_style {
+        StringStyle::Auto => builder.as_default(),
+        StringStyle::Basic => builder.as_basic(),
+        StringStyle::Literal => builder
+            .as_literal()
+            .ok_or(TomliError::StringStyleNotPossible("literal"))?,
+        StringStyle::MultilineBasic => builder.as_ml_basic(),
+        StringStyle::MultilineLiteral => builder
+            .as_ml_literal()
+            .ok_or(TomliError::StringStyleNotPossible("multiline literal"))?,
+    };
+
+    // toml_edit does not allow setting the representation directly, so we parse the rendered
+    // string to get a value that keeps it
+    let mut value =
+        Value::from_str(&string.to_toml_value()).map_err(TomliError::ValueToTomlError)?;
+    value.decor_mut().clear();
+    Ok(Item::Value(value))
+}
+
+// Insert an underscore between every group of `size` digits, starting from the right
+fn group_digits(digits: &str, size: usize) -> String {
+    let digits: Vec<char> = digits.chars().filter(|c| *c != '_').collect();
+    let mut grouped = String::new();
+    for (position, digit) in digits.iter().enumerate() {
+        if position > 0 && (digits.len() - position).is_multiple_of(size) {
+            grouped.push('_');
+        }
+        grouped.push(*digit);
+    }
+    grouped
+}
+
+// Render integers and floats in the requested representation
+fn format_number(value: Item, options: &SetOptions) -> Result<Item, TomliError> {
+    let repr = match value.as_value() {
+        Some(Value::Integer(integer)) => {
+            let number = *integer.value();
+            let (prefix, digits, group_size) = match options.int_format {
+                IntFormat::Keep if !options.group_digits => return Ok(value),
+                IntFormat::Keep => {
+                    let repr = integer.display_repr();
+                    match repr.get(..2) {
+                        Some(prefix @ ("0x" | "0b")) => {
+                            (prefix.to_string(), repr[2..].to_string(), 4)
+                        }
+                        Some("0o") => ("0o".to_string(), repr[2..].to_string(), 3),
+                        _ => (String::new(), repr.to_string(), 3),
+                    }
+                }
+                IntFormat::Decimal => (String::new(), number.to_string(), 3),
+                _ if number < 0 => return Err(TomliError::NegativeIntegerFormat),
+                IntFormat::Hex => ("0x".to_string(), format!("{number:x}"), 4),
+                IntFormat::Octal => ("0o".to_string(), format!("{number:o}"), 3),
+                IntFormat::Binary => ("0b".to_string(), format!("{number:b}"), 4),
+            };
+            // The sign is not a digit and must stay in front of the prefix
+            let (sign, digits) = match digits.strip_prefix(['-', '+']) {
+                Some(unsigned) => (&digits[..1], unsigned),
+                None => ("", digits.as_str()),
+            };
+            if options.group_digits {
+                format!("{sign}{prefix}{}", group_digits(digits, group_size))
+            } else {
+                format!("{sign}{prefix}{digits}")
+            }
+        }
+        Some(Value::Float(float)) if options.group_digits && float.value().is_finite() => {
+            let repr = float.display_repr();
+            // Only the integer part is grouped
+            let (sign, unsigned) = match repr.strip_prefix(['-', '+']) {
+                Some(unsigned) => (&repr[..1], unsigned),
+                None => ("", repr.as_ref()),
+            };
+            let end = unsigned.find(['.', 'e', 'E']).unwrap_or(unsigned.len());
+            format!(
+                "{sign}{}{}",
+                group_digits(&unsigned[..end], 3),
+                &unsigned[end..]
+            )
+        }
+        _ => return Ok(value),
+    };
+
+    let mut value = Value::from_str(&repr).map_err(TomliError::ValueToTomlError)?;
+    value.decor_mut().clear();
+    Ok(Item::Value(value))
+}
+
+// Parse a datetime and make sure that it has the flavor of the requested value type
+fn parse_datetime(value: &str, value_type: &ValueType) -> Result<Datetime, TomliError> {
+    let datetime = Datetime::from_str(value)?;
+    let (has_date, has_time, has_offset) = (
+        datetime.date.is_some(),
+        datetime.time.is_some(),
+        datetime.offset.is_some(),
+    );
+
+    match value_type {
+        ValueType::Date if !has_date || has_time => Err(TomliError::DatetimeTypeMismatch("date")),
+        ValueType::Time if has_date => Err(TomliError::DatetimeTypeMismatch("time")),
+        ValueType::LocalDatetime if !has_date || !has_time || has_offset => {
+            Err(TomliError::DatetimeTypeMismatch("local-datetime"))
+        }
+        ValueType::OffsetDatetime if !has_offset => {
+            Err(TomliError::DatetimeTypeMismatch("offset-datetime"))
+        }
+        _ => Ok(datetime),
+    }
+}
+
+// Determine the value type of an existing value, so that it can be kept when overwriting it
+fn existing_value_type(value: &Value) -> ValueType {
+    match value {
+        Value::String(_) => ValueType::Str,
+        Value::Integer(_) => ValueType::Int,
+        Value::Float(_) => ValueType::Float,
+        Value::Boolean(_) => ValueType::Bool,
+        Value::Datetime(datetime) => match (
+            datetime.value().date.is_some(),
+            datetime.value().time.is_some(),
+            datetime.value().offset.is_some(),
+        ) {
+            (_, _, true) => ValueType::OffsetDatetime,
+            (true, true, false) => ValueType::LocalDatetime,
+            (true, false, false) => ValueType::Date,
+            _ => ValueType::Time,
+        },
+        Value::Array(_) | Value::InlineTable(_) => ValueType::Toml,
+    }
+}
+
+/// Options that control how a value is set
+pub struct SetOptions {
+    pub value_type: ValueType,
+    pub string_style: StringStyle,
+    pub int_format: IntFormat,
+    pub group_digits: bool,
+    pub dotted_key: bool,
+    pub insert: bool,
+    pub keep_type: bool,
+    pub discard_decor: bool,
+}
+
+// Return the decor (whitespace and comments) of the value at `path` if it exists
+pub(crate) fn existing_decor(path: &[parser::Item], root: &Item) -> Option<Decor> {
+    query::parse_toml_path(path, root)
+        .ok()
+        .and_then(Item::as_value)
+        .map(|value| value.decor().clone())
+}
+
+// Replace `item` with `new_item` and apply `decor` to it if it's a value
+pub(crate) fn replace_item(item: &mut Item, new_item: Item, decor: Option<Decor>) {
+    *item = new_item;
+    if let (Some(decor), Some(value)) = (decor, item.as_value_mut()) {
+        *value.decor_mut() = decor;
+    }
+}
+
+/// A single value that should be set
+#[derive(Clone)]
+pub struct Assignment {
+    pub query: String,
+    pub value: String,
+    /// Overrides the value type from the options
+    pub value_type: Option<ValueType>,
+}
+
+impl FromStr for Assignment {
+    type Err = TomliError;
+
+    // Parse an assignment in the form QUERY=VALUE or QUERY:TYPE=VALUE
+    fn from_str(assignment: &str) -> Result<Self, Self::Err> {
+        // The first "=" that is not part of a filter or a quoted key separates the query from the value
+        let mut depth = 0;
+        let mut quote = None;
+        let separator = assignment.char_indices().find(|(_, c)| {
+            match (*c, quote) {
+                ('"' | '\'', None) => quote = Some(*c),
+                (c, Some(q)) if c == q => quote = None,
+                ('[', None) => depth += 1,
+                (']', None) => depth -= 1,
+                ('=', None) if depth == 0 => return true,
+                _ => (),
+            }
+            false
+        });
+        let Some((position, _)) = separator else {
+            return Err(TomliError::InvalidAssignment(assignment.to_string()));
+        };
+
+        let (query, value) = (&assignment[..position], &assignment[position + 1..]);
+        // A type is only split off if it is a known value type, so that slices stay untouched
+        let (query, value_type) = match query.rsplit_once(':') {
+            Some((query, value_type)) => match ValueType::from_str(value_type, false) {
+                Ok(value_type) => (query, Some(value_type)),
+                Err(_) => (&assignment[..position], None),
+            },
+            None => (query, None),
+        };
+
+        Ok(Assignment {
+            query: query.to_string(),
+            value: value.to_string(),
+            value_type,
+        })
+    }
+}
+
 pub fn exec(
     document: &mut DocumentMut,
-    query: &str,
-    value: &str,
-    value_type: ValueType,
-    dotted_key: bool,
-) -> Result<String, TomliError> {
+    assignment: &Assignment,
+    options: &SetOptions,
+) -> Result<(), TomliError> {
+    let query = assignment.query.as_str();
+    let value = assignment.value.as_str();
+    let value_type = assignment
+        .value_type
+        .as_ref()
+        .unwrap_or(&options.value_type);
     // Editing the whole document makes no sense
     // If the user wants to do this, then he should use echo (or a similiar tool) to edit the file manually
     if query == "." || query.is_empty() {
@@ -106,22 +608,90 @@ pub fn exec(
         ));
     }
 
-    let item = parse_toml_path(parser::evaluate(query)?, document.as_item_mut(), dotted_key)?;
-
-    if item.is_table() || item.is_array() || item.is_array_of_tables() {
+    let path = parser::evaluate(query)?;
+    if options.insert
+        && !path.iter().any(|segment| {
+            matches!(
+                segment,
+                parser::Item::ArrayIndex(_) | parser::Item::NegativeArrayIndex(_)
+            )
+        })
+    {
         return Err(TomliError::InvalidInputQuery(
             "set",
-            "Transforming a table or an array to a key / pair value is not allowed",
+            "Inserting a value requires an array index in the query",
         ));
     }
 
-    match value_type {
-        ValueType::Str => *item = toml_edit::value(value),
-        ValueType::Int => *item = toml_edit::value::<i64>(i64::from_str(value)?),
-        ValueType::Float => *item = toml_edit::value::<f64>(f64::from_str(value)?),
-        ValueType::Bool => *item = toml_edit::value::<bool>(bool::from_str(value)?),
-        ValueType::Datetime => *item = toml_edit::value::<Datetime>(Datetime::from_str(value)?),
-    };
+    // The number representation can not be applied to types that never produce numbers
+    if (!matches!(options.int_format, IntFormat::Keep) || options.group_digits)
+        && !options.keep_type
+        && !matches!(
+            value_type,
+            ValueType::Int | ValueType::Float | ValueType::Auto | ValueType::Toml
+        )
+    {
+        return Err(TomliError::NumberFormatNotPossible);
+    }
+
+    let paths = expand_toml_path(path, document.as_item())?;
+    // A selector that matches nothing is most likely a mistake in the query
+    if paths.is_empty() {
+        return Err(TomliError::NoMatch(query.to_string()));
+    }
+
+    for path in paths {
+        // Inserted elements never overwrite an existing value
+        let existing_value = query::parse_toml_path(&path, document.as_item())
+            .ok()
+            .filter(|_| !options.insert)
+            .and_then(Item::as_value);
+        let value = match existing_value {
+            Some(existing_value) if options.keep_type => {
+                let value = parse_value(
+                    value,
+                    &existing_value_type(existing_value),
+                    &options.string_style,
+                )?;
+                // Values of type toml can have any type, so we have to make sure it's the same
+                if value.type_name() != existing_value.type_name() {
+                    return Err(TomliError::ValueTypeMismatch(existing_value.type_name()));
+                }
+                value
+            }
+            _ => parse_value(value, value_type, &options.string_style)?,
+        };
+        let value = format_number(value, options)?;
+        // Keep the comments and whitespace around the value that is replaced
+        let decor = existing_value
+            .filter(|_| !options.discard_decor)
+            .map(|value| value.decor().clone());
+
+        let item = parse_toml_path(
+            &path,
+            document.as_item_mut(),
+            options.dotted_key,
+            options.insert,
+        )?;
+
+        // Arrays can only be replaced by other arrays or inline tables
+        let is_composite = value.is_array() || value.is_inline_table();
+        if item.is_table() || item.is_array_of_tables() || (item.is_array() && !is_composite) {
+            return Err(TomliError::InvalidInputQuery(
+                "set",
+                "Transforming a table or an array to a key / pair value is not allowed",
+            ));
+        }
+
+        // An inserted or appended element keeps the decor of its placeholder to match its
+        // neighbours
+        let decor = if options.insert || matches!(path.last(), Some(parser::Item::Append)) {
+            item.as_value().map(|value| value.decor().clone())
+        } else {
+            decor
+        };
+        replace_item(item, value, decor);
+    }
 
-    Ok(document.to_string())
+    Ok(())
 }
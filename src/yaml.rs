@@ -0,0 +1,16 @@
+use serde_json::Value as JsonValue;
+
+use crate::errors::TomliError;
+
+/// Parse a YAML document into a JSON value, so that it can be converted like JSON
+pub fn from_str(input: &str) -> Result<JsonValue, TomliError> {
+    Ok(serde_norway::from_str(input)?)
+}
+
+/// Serialize a JSON value as YAML
+pub fn to_string(value: &JsonValue) -> String {
+    serde_norway::to_string(value)
+        .expect("BUG: Could not serialize YAML value")
+        .trim_end()
+        .to_string()
+}
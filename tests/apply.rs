@@ -0,0 +1,66 @@
+mod common;
+
+use common::generic_test;
+use std::process::Command;
+
+macro_rules! apply_test {
+    ($test_name:ident, $script:literal, $expected:literal) => {
+        generic_test!(
+            $test_name,
+            vec!["apply", "--filepath", "tests/files/comments.toml", $script],
+            $expected
+        );
+    };
+}
+
+/*
+* Tests that should always pass
+*/
+
+// Test if every operation in the script is applied in order
+apply_test!(
+    should_apply_all_operations,
+    "tests/files/operations.txt",
+    r#"# Package metadata
+[package]
+version = "0.2.0" # Bumped on every release
+keywords = ["toml cli"]
+display_name = "tomli"  # The name of the binary
+
+[permissions]
+mode = 0o755
+"#
+);
+
+// Test if backslashes are taken literally unless they escape a quote inside of double quotes
+apply_test!(
+    should_keep_backslashes_in_words,
+    "tests/files/escaped_operations.txt",
+    r#"# Package metadata
+[package]
+name = "tomli"  # The name of the binary
+version = "0.1.0" # Bumped on every release
+path = 'C:\tomli\bin'
+description = 'A "simple" CLI in C:\tomli'
+pattern = '\d+'
+home = "C:\\home"
+"#
+);
+
+/*
+* Tests that should always fail
+*/
+
+// Test if the failing operation is reported
+apply_test!(
+    should_report_failing_operation,
+    "tests/files/invalid_operations.txt",
+    "Operation on line 3 failed: set --type int package.mode 755o\n\nCould not convert the given value to an integer (i64)\n"
+);
+
+// Test if an unknown operation is reported
+apply_test!(
+    should_fail_on_unknown_operation,
+    "tests/files/comments.toml",
+    "Operation on line 2 failed: [package]\n\nInvalid operation: unrecognized subcommand '[package]'\n"
+);
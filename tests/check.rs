@@ -0,0 +1,133 @@
+use std::process::Command;
+
+// In check mode the exit code signals whether the document would be changed, so it is checked
+// together with the output
+macro_rules! check_test {
+    ($test_name:ident, $args:expr, $exit_code:literal, $expected:literal) => {
+        #[test]
+        fn $test_name() {
+            let result = Command::new(env!("CARGO_BIN_EXE_tomli"))
+                .arg("--check")
+                .args($args)
+                .output()
+                .expect("Command could not be executed");
+
+            assert_eq!(result.status.code(), Some($exit_code));
+            assert_eq!(String::from_utf8_lossy(&result.stdout), $expected);
+        }
+    };
+}
+
+/*
+* Tests that should always pass
+*/
+
+// Test if a change is printed as a unified diff
+check_test!(
+    should_print_diff_of_set,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/base.toml",
+        "server.port",
+        "9090",
+        "-t",
+        "int"
+    ],
+    1,
+    r#"--- tests/files/base.toml
++++ tests/files/base.toml
+@@ -1,7 +1,7 @@
+ # Base configuration
+ [server]
+ host = "localhost" # Listen address
+-port = 8080
++port = 9090
+ features = ["auth", "metrics"]
+ 
+ [database]
+"#
+);
+
+// Test if the file is not modified even if --in-place is set
+check_test!(
+    should_not_write_into_file,
+    vec![
+        "delete",
+        "--in-place",
+        "--filepath",
+        "tests/files/base.toml",
+        "server"
+    ],
+    1,
+    r#"--- tests/files/base.toml
++++ tests/files/base.toml
+@@ -1,8 +1,3 @@
+-# Base configuration
+-[server]
+-host = "localhost" # Listen address
+-port = 8080
+-features = ["auth", "metrics"]
+ 
+ [database]
+ url = "postgres://localhost/app"
+"#
+);
+
+// Test if nothing is printed when the document would not change
+check_test!(
+    should_succeed_without_changes,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/base.toml",
+        "server.port",
+        "8080",
+        "-t",
+        "int"
+    ],
+    0,
+    ""
+);
+
+// Test if the diff of copy is made against the destination file
+check_test!(
+    should_print_diff_of_copy,
+    vec![
+        "copy",
+        "--filepath",
+        "tests/files/base.toml",
+        "server.host",
+        "tests/files/override.toml",
+        "server.host"
+    ],
+    1,
+    r#"--- tests/files/override.toml
++++ tests/files/override.toml
+@@ -1,6 +1,7 @@
+ [server]
+ port = 9090
+ features = ["metrics", "tracing"]
++host = "localhost" # Listen address
+ 
+ [database]
+ pool = { max = 20 }
+"#
+);
+
+/*
+* Tests that should always fail
+*/
+
+// Test if an error is signaled with its own exit code
+check_test!(
+    should_fail_if_key_does_not_exist,
+    vec![
+        "delete",
+        "--filepath",
+        "tests/files/base.toml",
+        "server.missing"
+    ],
+    2,
+    ""
+);
@@ -0,0 +1,97 @@
+mod common;
+
+use common::generic_test;
+use std::process::Command;
+
+macro_rules! convert_test {
+    ($test_name:ident, $file:literal, [ $( $arguments:expr ),* ], $expected:literal) => {
+        generic_test!(
+            $test_name,
+            vec![
+                "convert",
+                "--filepath",
+                concat!("tests/files/", $file),
+                $( $arguments ),*
+            ],
+            $expected
+        );
+    };
+}
+
+/*
+* Tests that should always pass
+*/
+
+// Test if a TOML document is converted into JSON by default
+convert_test!(
+    should_convert_toml_to_json,
+    "array_of_tables.toml",
+    ["--compact"],
+    r#"{"bin":[{"name":"tomli","path":"src/main.rs","priority":1},{"name":"tomli-helper","path":"src/helper.rs","priority":2},{"name":"other","path":"src/other.rs","priority":3}]}"#
+);
+
+// Test if a TOML document can be converted into YAML
+convert_test!(
+    should_convert_toml_to_yaml,
+    "array_of_tables.toml",
+    ["--to", "yaml"],
+    r#"bin:
+- name: tomli
+  path: src/main.rs
+  priority: 1
+- name: tomli-helper
+  path: src/helper.rs
+  priority: 2
+- name: other
+  path: src/other.rs
+  priority: 3"#
+);
+
+// Test if objects are converted into tables and arrays of objects into arrays of tables
+convert_test!(
+    should_convert_json_to_toml,
+    "valid.json",
+    ["--from", "json", "--to", "toml"],
+    r#"float = 1.5
+
+[package]
+name = "tomli"
+version = "0.5.0"
+keywords = ["toml", "cli"]
+
+[package.metadata.docs]
+all-features = true
+
+[[bin]]
+name = "tomli"
+path = "src/main.rs"
+"#
+);
+
+// Test if a YAML document can be converted into TOML
+convert_test!(
+    should_convert_yaml_to_toml,
+    "valid.yaml",
+    ["--from", "yaml", "--to", "toml"],
+    r#"[package]
+name = "tomli"
+version = "0.5.0"
+keywords = ["toml", "cli"]
+
+[[bin]]
+name = "tomli"
+path = "src/main.rs"
+"#
+);
+
+/*
+* Tests that should always fail
+*/
+
+// Test if converting a document with invalid syntax fails
+convert_test!(
+    should_print_error_message_on_invalid_json,
+    "valid.toml",
+    ["--from", "json"],
+    "The provided JSON document has an invalid syntax:\n\nexpected ident at line 1 column 3\n"
+);
@@ -71,3 +71,21 @@ copy_test!(
 new_inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
 "#
 );
+
+// Test if copying into an existing key keeps the comments of the replaced value
+generic_test!(
+    should_keep_decor_of_replaced_value,
+    vec![
+        "copy",
+        "--filepath",
+        "tests/files/comments.toml",
+        "package.version",
+        "tests/files/comments.toml",
+        "package.name"
+    ],
+    r#"# Package metadata
+[package]
+name = "0.1.0"  # The name of the binary
+version = "0.1.0" # Bumped on every release
+"#
+);
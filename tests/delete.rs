@@ -126,3 +126,161 @@ array = [1, 2, 3]
 key = "value"
 "#
 );
+
+// Test if deleting with a wildcard deletes every match
+delete_test!(
+    should_delete_every_match_of_wildcard,
+    "table.array_of_tables[*].array",
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if deleting every element of an array with a wildcard works
+delete_test!(
+    should_delete_every_element_in_array_with_wildcard,
+    "table.array[*]",
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = []
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if deleting a slice of an array works
+delete_test!(
+    should_delete_slice_of_array,
+    "table.array[1:]",
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if deleting with recursive descent deletes every match
+delete_test!(
+    should_delete_every_match_of_recursive_descent,
+    "..array",
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if deleting the elements matching a filter works
+generic_test!(
+    should_delete_elements_matching_filter,
+    vec![
+        "delete",
+        "--filepath",
+        "tests/files/array_of_tables.toml",
+        "bin[?priority >= 2]"
+    ],
+    r#"[[bin]]
+name = "tomli"
+path = "src/main.rs"
+priority = 1
+"#
+);
+
+// Test if a filter that matches nothing is ignored with --if-exists
+generic_test!(
+    should_exit_without_error_if_filter_matches_nothing,
+    vec![
+        "delete",
+        "--if-exists",
+        "--filepath",
+        "tests/files/array_of_tables.toml",
+        "bin[?name == \"nope\"]"
+    ],
+    r#"[[bin]]
+name = "tomli"
+path = "src/main.rs"
+priority = 1
+
+[[bin]]
+name = "tomli-helper"
+path = "src/helper.rs"
+priority = 2
+
+[[bin]]
+name = "other"
+path = "src/other.rs"
+priority = 3
+"#
+);
+
+/*
+* Tests that should always fail
+*/
+
+// Test if a filter that matches nothing is reported
+generic_test!(
+    should_fail_if_filter_matches_nothing,
+    vec![
+        "delete",
+        "--filepath",
+        "tests/files/array_of_tables.toml",
+        "bin[?name == \"nope\"]"
+    ],
+    "The query did not match anything: bin[?name == \"nope\"]\n"
+);
@@ -0,0 +1,59 @@
+use std::process::Command;
+
+// The exit code of the diff command signals whether the documents differ, so it is checked
+// together with the output
+macro_rules! diff_test {
+    ($test_name:ident, $other:literal, $exit_code:literal, $expected:literal) => {
+        #[test]
+        fn $test_name() {
+            let result = Command::new(env!("CARGO_BIN_EXE_tomli"))
+                .args(["diff", "--filepath", "tests/files/base.toml", $other])
+                .output()
+                .expect("Command could not be executed");
+
+            assert_eq!(result.status.code(), Some($exit_code));
+            assert_eq!(String::from_utf8_lossy(&result.stdout), $expected);
+        }
+    };
+}
+
+/*
+* Tests that should always pass
+*/
+
+// Test if every added, removed and changed item is reported
+diff_test!(
+    should_report_differences,
+    "tests/files/override.toml",
+    1,
+    r#"- server.host = "localhost"
+~ server.port = 8080 -> 9090
+~ server.features[0] = "auth" -> "metrics"
+~ server.features[1] = "metrics" -> "tracing"
+- database.url = "postgres://localhost/app"
+- database.pool.min = 1
+~ database.pool.max = 5 -> 20
+~ workers[0].name = "mail" -> "report"
++ logging = { level = "debug" }
+"#
+);
+
+// Test if formatting and the order of keys are ignored
+diff_test!(
+    should_ignore_formatting_and_key_order,
+    "tests/files/base_reformatted.toml",
+    0,
+    ""
+);
+
+/*
+* Tests that should always fail
+*/
+
+// Test if an error is signaled with its own exit code
+diff_test!(
+    should_fail_if_other_document_does_not_exist,
+    "tests/files/does_not_exist.toml",
+    2,
+    ""
+);
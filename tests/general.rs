@@ -68,7 +68,7 @@ array = [1, 2, 3]
 [second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
 key = "value"
 "#;
-    let mut file = tempfile::NamedTempFile::new().expect("Could not create temporary file");
+    let file = tempfile::NamedTempFile::new().expect("Could not create temporary file");
     std::fs::copy("tests/files/valid.toml", file.path()).expect("Could not copy file");
     let result = Command::new(env!("CARGO_BIN_EXE_tomli"))
         .args(vec![
@@ -85,8 +85,8 @@ key = "value"
         assert!(result.status.success());
         assert!(result.stdout.is_empty());
         assert!(result.stderr.is_empty());
-        let mut actual = String::new();
-        file.read_to_string(&mut actual).expect("Could not read");
+        // The file is replaced, so it has to be opened again
+        let actual = std::fs::read_to_string(file.path()).expect("Could not read");
         assert_eq!(actual, expected)
     } else {
         panic!("Command could not be executed --> {}", result.unwrap_err());
@@ -114,7 +114,7 @@ array = [1, 2, 3]
 [second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
 key = "value"
 "#;
-    let mut file = tempfile::NamedTempFile::new().expect("Could not create temporary file");
+    let file = tempfile::NamedTempFile::new().expect("Could not create temporary file");
     std::fs::copy("tests/files/valid.toml", file.path()).expect("Could not copy file");
     let result = Command::new(env!("CARGO_BIN_EXE_tomli"))
         .args(vec![
@@ -130,8 +130,8 @@ key = "value"
         assert!(result.status.success());
         assert!(result.stdout.is_empty());
         assert!(result.stderr.is_empty());
-        let mut actual = String::new();
-        file.read_to_string(&mut actual).expect("Could not read");
+        // The file is replaced, so it has to be opened again
+        let actual = std::fs::read_to_string(file.path()).expect("Could not read");
         assert_eq!(actual, expected)
     } else {
         panic!("Command could not be executed --> {}", result.unwrap_err());
@@ -166,3 +166,83 @@ fn should_query_and_not_write_into_file() {
         panic!("Command could not be executed --> {}", result.unwrap_err());
     }
 }
+
+#[cfg(target_family = "unix")]
+#[test]
+fn should_keep_permissions_when_writing_into_file() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let file = tempfile::NamedTempFile::new().expect("Could not create temporary file");
+    std::fs::copy("tests/files/valid.toml", file.path()).expect("Could not copy file");
+    std::fs::set_permissions(file.path(), std::fs::Permissions::from_mode(0o640))
+        .expect("Could not set permissions");
+    let result = Command::new(env!("CARGO_BIN_EXE_tomli"))
+        .args(vec![
+            "set",
+            "--in-place",
+            "--filepath",
+            &file.path().to_string_lossy(),
+            "table.key",
+            "value1",
+        ])
+        .output();
+
+    if let Ok(result) = result {
+        assert!(result.status.success());
+        let metadata = std::fs::metadata(file.path()).expect("Could not read metadata");
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o640);
+        // No temporary files should be left behind
+        let parent = file.path().parent().expect("File has no parent");
+        let name = file.path().file_name().expect("File has no name");
+        assert!(
+            !std::fs::read_dir(parent)
+                .expect("Could not read directory")
+                .any(|entry| {
+                    entry
+                        .expect("Could not read entry")
+                        .file_name()
+                        .to_string_lossy()
+                        .starts_with(&format!(".{}.tomli-", name.to_string_lossy()))
+                })
+        );
+    } else {
+        panic!("Command could not be executed --> {}", result.unwrap_err());
+    }
+}
+
+#[test]
+fn should_create_backup_when_writing_into_file() {
+    let directory = tempfile::tempdir().expect("Could not create temporary directory");
+    let filepath = directory.path().join("Cargo.toml");
+    std::fs::copy("tests/files/valid.toml", &filepath).expect("Could not copy file");
+    let run = |backup: &str, value: &str| {
+        Command::new(env!("CARGO_BIN_EXE_tomli"))
+            .args(vec![
+                "set",
+                "--in-place",
+                backup,
+                "--filepath",
+                &filepath.to_string_lossy(),
+                "table.number",
+                value,
+            ])
+            .output()
+            .expect("Command could not be executed")
+    };
+
+    // Without a suffix, .bak is used
+    let result = run("--backup", "3");
+    assert!(result.status.success());
+    assert_eq!(
+        std::fs::read_to_string(directory.path().join("Cargo.toml.bak")).expect("Could not read"),
+        std::fs::read_to_string("tests/files/valid.toml").expect("Could not read file")
+    );
+
+    let original = std::fs::read_to_string(&filepath).expect("Could not read");
+    let result = run("--backup=.orig", "4");
+    assert!(result.status.success());
+    assert_eq!(
+        std::fs::read_to_string(directory.path().join("Cargo.toml.orig")).expect("Could not read"),
+        original
+    );
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use common::generic_test;
+use std::process::Command;
+
+macro_rules! merge_test {
+    ($test_name:ident, [ $( $arguments:expr ),* ], $expected:literal) => {
+        generic_test!(
+            $test_name,
+            vec![
+                "merge",
+                "--filepath",
+                "tests/files/base.toml",
+                $( $arguments ),*
+            ],
+            $expected
+        );
+    };
+}
+
+/*
+* Tests that should always pass
+*/
+
+// Test if merging replaces values and arrays and keeps everything else
+merge_test!(
+    should_merge_documents,
+    ["tests/files/override.toml"],
+    r#"# Base configuration
+[server]
+host = "localhost" # Listen address
+port = 9090
+features = ["metrics", "tracing"]
+
+[database]
+url = "postgres://localhost/app"
+pool = { min = 1, max = 20 }
+
+[[workers]]
+name = "report"
+
+[logging]
+level = "debug"
+"#
+);
+
+// Test if merging with the union strategy only adds new array elements
+merge_test!(
+    should_merge_arrays_as_union,
+    ["tests/files/override.toml", "--arrays", "union"],
+    r#"# Base configuration
+[server]
+host = "localhost" # Listen address
+port = 9090
+features = ["auth", "metrics", "tracing"]
+
+[database]
+url = "postgres://localhost/app"
+pool = { min = 1, max = 20 }
+
+[[workers]]
+name = "mail"
+
+[[workers]]
+name = "report"
+
+[logging]
+level = "debug"
+"#
+);
+
+// Test if merging into a table that does not exist creates it
+merge_test!(
+    should_merge_into_new_table,
+    ["tests/files/override.toml", "profiles.prod.database"],
+    r#"# Base configuration
+[server]
+host = "localhost" # Listen address
+port = 8080
+features = ["auth", "metrics"]
+
+[database]
+url = "postgres://localhost/app"
+pool = { min = 1, max = 5 }
+
+[[workers]]
+name = "mail"
+
+[profiles.prod.database.server]
+port = 9090
+features = ["metrics", "tracing"]
+
+[profiles.prod.database.database]
+pool = { max = 20 }
+
+[profiles.prod.database.logging]
+level = "debug"
+
+[[profiles.prod.database.workers]]
+name = "report"
+"#
+);
+
+/*
+* Tests that should always fail
+*/
+
+// Test if merging into an array with a key fails
+merge_test!(
+    should_fail_to_merge_into_array_with_key,
+    ["tests/files/override.toml", "server.features.extra"],
+    "\"extra\" was used wrongly in the query.\nThis usually means that a key which has a type (e.g. key / value pair) was used as another type (e.g. array)\n"
+);
@@ -0,0 +1,166 @@
+mod common;
+
+use common::generic_test;
+use std::process::Command;
+
+macro_rules! move_test {
+    ($test_name:ident, $source_query:literal, $destination_query:literal, $expected:literal) => {
+        generic_test!(
+            $test_name,
+            vec![
+                "move",
+                "--filepath",
+                "tests/files/dependencies.toml",
+                $source_query,
+                $destination_query
+            ],
+            $expected
+        );
+    };
+}
+
+/*
+* Tests that should always pass
+*/
+
+// Test if renaming a key keeps its position and comments
+move_test!(
+    should_rename_key_in_place,
+    "package.version",
+    "package.release",
+    r#"# Package metadata
+[package]
+name = "tomli"
+# Bumped on every release
+release = "0.1.0" # Managed by CI
+keywords = ["toml", "cli", "query"]
+
+[dev-dependencies.tempfile]
+# Pinned until the next major release
+version = "3" # Used by the integration tests
+
+[dependencies]
+clap = "4"
+"#
+);
+
+// Test if moving a table into another table keeps its comments
+move_test!(
+    should_move_table_into_another_table,
+    "dev-dependencies.tempfile",
+    "dependencies.tempfile",
+    r#"# Package metadata
+[package]
+name = "tomli"
+# Bumped on every release
+version = "0.1.0" # Managed by CI
+keywords = ["toml", "cli", "query"]
+
+[dependencies]
+clap = "4"
+
+[dependencies.tempfile]
+# Pinned until the next major release
+version = "3" # Used by the integration tests
+"#
+);
+
+// Test if moving an element of an array shifts the other elements
+move_test!(
+    should_move_element_in_array,
+    "package.keywords[2]",
+    "package.keywords[0]",
+    r#"# Package metadata
+[package]
+name = "tomli"
+# Bumped on every release
+version = "0.1.0" # Managed by CI
+keywords = ["query", "toml", "cli"]
+
+[dev-dependencies.tempfile]
+# Pinned until the next major release
+version = "3" # Used by the integration tests
+
+[dependencies]
+clap = "4"
+"#
+);
+
+// Test if moving the first element of an array keeps the formatting of the new first element
+move_test!(
+    should_move_first_element_in_array,
+    "package.keywords[0]",
+    "package.keywords[2]",
+    r#"# Package metadata
+[package]
+name = "tomli"
+# Bumped on every release
+version = "0.1.0" # Managed by CI
+keywords = ["cli", "query", "toml"]
+
+[dev-dependencies.tempfile]
+# Pinned until the next major release
+version = "3" # Used by the integration tests
+
+[dependencies]
+clap = "4"
+"#
+);
+
+// Test if moving a value into an inline table does not take its comments and whitespace along
+generic_test!(
+    should_move_value_into_inline_table,
+    vec![
+        "move",
+        "--filepath",
+        "tests/files/inline_tables.toml",
+        "a.y",
+        "b.inl.y"
+    ],
+    r#"[a]
+
+[a.sub]
+z = 1
+
+[b]
+inl = { p = 1, q = 2 , y = 3 }
+"#
+);
+
+// Test if moving a table into an inline table converts it without taking its formatting along
+generic_test!(
+    should_move_table_into_inline_table,
+    vec![
+        "move",
+        "--filepath",
+        "tests/files/inline_tables.toml",
+        "a.sub",
+        "b.inl.s"
+    ],
+    r#"[a]
+y = 3 # Moved into the inline table
+
+[b]
+inl = { p = 1, q = 2 , s = { z = 1 } }
+"#
+);
+
+/*
+* Tests that should always fail
+*/
+
+// Test if moving an item to an existing key fails
+move_test!(
+    should_not_overwrite_existing_key,
+    "package.name",
+    "package.version",
+    "Key already exists: version\n"
+);
+
+// Test if moving an item into itself fails
+move_test!(
+    should_not_move_item_into_itself,
+    "package",
+    "package.inner",
+    "Invalid query for command move\nAn item can not be moved into itself\n"
+);
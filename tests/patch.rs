@@ -0,0 +1,81 @@
+mod common;
+
+use common::generic_test;
+use std::process::Command;
+
+macro_rules! patch_test {
+    ($test_name:ident, $patch:literal, $expected:literal) => {
+        generic_test!(
+            $test_name,
+            vec!["patch", "--filepath", "tests/files/base.toml", $patch],
+            $expected
+        );
+    };
+}
+
+/*
+* Tests that should always pass
+*/
+
+// Test if every operation of the patch is applied
+patch_test!(
+    should_apply_patch,
+    "tests/files/patch.json",
+    r#"# Base configuration
+[server]
+host = "localhost" # Listen address
+port = 9090
+features = ["auth", "tracing", "metrics"]
+
+[database]
+pool = { max = 5 }
+address = "postgres://localhost/app"
+
+[[workers]]
+name = "mail"
+
+[[workers]]
+name = "mail"
+
+[logging]
+level = "debug"
+"#
+);
+
+// Test if copied values do not take their comments along to a new key
+patch_test!(
+    should_not_copy_comments_of_value,
+    "tests/files/copy_patch.json",
+    r#"# Base configuration
+[server]
+host = "localhost" # Listen address
+port = 8080
+features = ["auth", "metrics", "localhost"]
+
+[database]
+url = "postgres://localhost/app"
+pool = { min = 1, max = 5 }
+host = "localhost"
+
+[[workers]]
+name = "mail"
+"#
+);
+
+/*
+* Tests that should always fail
+*/
+
+// Test if a failing test operation aborts the patch
+patch_test!(
+    should_abort_patch_if_test_fails,
+    "tests/files/failing_patch.json",
+    "Patch operation 1 failed:\n\nTest failed, the value at \"/server/host\" is different\n"
+);
+
+// Test if a patch that is not a list of operations fails
+patch_test!(
+    should_fail_if_patch_is_not_a_list,
+    "tests/files/valid.json",
+    "Invalid patch: A patch has to be an array of operations\n"
+);
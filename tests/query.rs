@@ -11,6 +11,31 @@ macro_rules! query_test {
             $expected
         );
     };
+    ($test_name:ident, $query:literal, [ $( $arguments:expr ),* ], $expected:literal) => {
+        generic_test!(
+            $test_name,
+            vec![
+                "query",
+                "--filepath",
+                "tests/files/valid.toml",
+                $( $arguments ),*,
+                $query
+            ],
+            $expected
+        );
+    };
+    ($test_name:ident, $file:literal, $query:literal, $expected:literal) => {
+        generic_test!(
+            $test_name,
+            vec![
+                "query",
+                "--filepath",
+                concat!("tests/files/", $file),
+                $query
+            ],
+            $expected
+        );
+    };
 }
 
 /*
@@ -90,6 +115,164 @@ query_test!(
     " \"value\""
 );
 
+// Test if a wildcard returns every item in a table
+query_test!(
+    should_print_every_item_in_table_with_wildcard,
+    "second_table.*.*",
+    " \"value\""
+);
+// Test if a wildcard returns every element in an array of tables
+query_test!(
+    should_print_every_key_in_array_of_tables_with_wildcard,
+    "table.array_of_tables[*].key2",
+    " \"value2\"\n \"value2\""
+);
+// Test if a wildcard returns every element in an array
+query_test!(
+    should_print_every_element_in_array_with_wildcard,
+    "table.array[3][*]",
+    "4\n 5\n 6\n { name = \"inline_table_in_array\", another_array = [8, 9]}"
+);
+// Test if branches that do not contain the rest of the query are skipped
+query_test!(
+    should_skip_items_without_key_with_wildcard,
+    "table.*.inline_key",
+    " \"inline_value\""
+);
+
+// Test if negative indices count from the end of an array
+query_test!(should_print_last_element_in_array, "table.array[-2]", " 3");
+query_test!(
+    should_print_last_element_in_array_of_tables,
+    "table.array_of_tables[-1].key2",
+    " \"value2\""
+);
+// Test if slices return a range of elements
+query_test!(should_print_slice_of_array, "table.array[0:2]", "1\n 2");
+query_test!(
+    should_print_slice_of_array_with_negative_bounds,
+    "table.array[-3:-1]",
+    " 2\n 3"
+);
+query_test!(should_print_empty_slice_of_array, "table.array[3:1]", "");
+
+// Test if recursive descent returns every item with the given key at any depth
+query_test!(
+    should_print_every_matching_key_with_recursive_descent,
+    "..key2",
+    " \"value2\"\n \"value2\""
+);
+query_test!(
+    should_print_nested_matching_keys_with_recursive_descent,
+    "table..another_array[0]",
+    "8"
+);
+query_test!(
+    should_print_nothing_if_recursive_descent_does_not_match,
+    "..I_dont_exist",
+    ""
+);
+
+// Test if filters select the matching elements of an array of tables
+query_test!(
+    should_print_element_matching_equal_filter,
+    "array_of_tables.toml",
+    "bin[?name == \"tomli\"].path",
+    " \"src/main.rs\""
+);
+query_test!(
+    should_print_elements_matching_not_equal_filter,
+    "array_of_tables.toml",
+    "bin[?name != \"other\"].priority",
+    " 1\n 2"
+);
+query_test!(
+    should_print_elements_matching_comparison_filter,
+    "array_of_tables.toml",
+    "bin[?priority > 1].name",
+    " \"tomli-helper\"\n \"other\""
+);
+query_test!(
+    should_print_elements_matching_regex_filter,
+    "array_of_tables.toml",
+    "bin[?name =~ '^tomli'].path",
+    " \"src/main.rs\"\n \"src/helper.rs\""
+);
+// Test if offset datetimes are compared by the instant they represent and other flavors are
+// never matched
+query_test!(
+    should_print_elements_matching_datetime_filter,
+    "releases.toml",
+    "release[?published == 1979-05-27T07:32:00Z].version",
+    " \"0.1.0\"\n \"0.2.0\""
+);
+query_test!(
+    should_print_elements_matching_datetime_comparison_filter,
+    "releases.toml",
+    "release[?published < 1979-05-27T08:00:00+00:00].version",
+    " \"0.1.0\"\n \"0.2.0\""
+);
+// Test if filters can be used on inline tables in an array
+query_test!(
+    should_print_inline_table_matching_filter,
+    "table.array[3][?name == \"inline_table_in_array\"].another_array",
+    " [8, 9]"
+);
+
+// Test if strings are printed without quotes and decor in raw mode
+query_test!(
+    should_print_raw_string,
+    "table.key_with_decorator",
+    ["--raw"],
+    "value"
+);
+// Test if other values are printed without decor in raw mode
+query_test!(should_print_raw_number, "table.number", ["--raw"], "2");
+// Test if arrays are printed with one element per line in raw mode
+query_test!(
+    should_print_raw_array,
+    "table.array",
+    ["-r"],
+    "1\n2\n3\n[4, 5, 6, { name = \"inline_table_in_array\", another_array = [8, 9]}]"
+);
+// Test if every match is printed in raw mode
+query_test!(
+    should_print_raw_strings_with_wildcard,
+    "table.array_of_tables[*].key",
+    ["--raw"],
+    "value\nvalue"
+);
+
+// Test if values are printed as JSON
+query_test!(
+    should_print_inline_table_as_json,
+    "table.inline_table",
+    ["--output", "json"],
+    r#"{
+  "inline_key": "inline_value",
+  "array_in_inline_table": []
+}"#
+);
+query_test!(
+    should_print_array_of_tables_as_compact_json,
+    "table.array_of_tables",
+    ["--output", "json", "--compact"],
+    r#"[{"key":"value","key2":"value2","array":[1,2,3]},{"key":"value","key2":"value2","array":[1,2,3]}]"#
+);
+query_test!(
+    should_print_string_as_json,
+    "table.key_with_decorator",
+    ["-o", "json"],
+    "\"value\""
+);
+// Test if strings are printed without quotes when combining JSON and raw mode
+query_test!(
+    should_print_raw_string_as_json,
+    "table.key_with_decorator",
+    ["-o", "json", "-r"],
+    "value"
+);
+
 /*
 * Tests that should always fail
 */
@@ -121,3 +304,36 @@ query_test!(
     "table.array_of_tables[5]",
     "Index (\"5\") is out of bounds\n"
 );
+
+// Test if using a wildcard on a value fails
+query_test!(
+    should_print_invalid_key_access_for_wildcard_on_value,
+    "table.number.*",
+    "\"*\" was used wrongly in the query.\nThis usually means that a key which has a type (e.g. key / value pair) was used as another type (e.g. array)\n"
+);
+
+query_test!(
+    should_print_negative_index_out_of_bounds,
+    "table.array[-5]",
+    "Index (\"-5\") is out of bounds\n"
+);
+
+// Test if using a filter on a table fails
+query_test!(
+    should_print_invalid_key_access_for_filter_on_table,
+    "table[?key == 1]",
+    "\"?key == 1\" was used wrongly in the query.\nThis usually means that a key which has a type (e.g. key / value pair) was used as another type (e.g. array)\n"
+);
+// Test if an invalid regular expression fails
+query_test!(
+    should_print_invalid_regex,
+    "table.array_of_tables[?key =~ \"(\"]",
+    "The provided regular expression is invalid:\n\nregex parse error:\n    (\n    ^\nerror: unclosed group\n"
+);
+
+// Test if using the append syntax fails
+query_test!(
+    should_print_append_not_allowed,
+    "table.array[+]",
+    "Appending (\"[+]\") can only be used when setting a value\n"
+);
@@ -484,6 +484,898 @@ key = "value"
 "#
 );
 
+// Test if setting a value with a wildcard sets every match
+set_test!(
+    should_set_value_with_wildcard,
+    "table.array_of_tables[*].key",
+    "new",
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+
+[[table.array_of_tables]]
+key = "new"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "new"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if matches of a wildcard that are scalar values are skipped instead of overwritten
+generic_test!(
+    should_skip_scalar_values_matched_by_wildcard,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/mixed_dependencies.toml",
+        "dependencies.*.version",
+        "3"
+    ],
+    r#"[dependencies]
+foo = { version = "3" }
+bar = "2"
+
+[dependencies.baz]
+path = "../baz"
+version = "3"
+"#
+);
+
+// Test if setting a value with a negative index works
+set_test!(
+    should_set_value_with_negative_index,
+    "table.array[-3]",
+    "5",
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, "5", 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if setting a value with recursive descent sets every match
+set_test!(
+    should_set_value_with_recursive_descent,
+    "..key",
+    "value1",
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+
+[[table.array_of_tables]]
+key = "value1"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value1"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value1"
+"#
+);
+
+// Test if a match of a recursive descent that is inside of another match is not set again
+generic_test!(
+    should_set_only_outermost_match_of_recursive_descent,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/nested_keys.toml",
+        "--type",
+        "int",
+        "..timeout",
+        "60"
+    ],
+    r#"[server]
+timeout = 60
+"#
+);
+
+// Test if setting a value in the elements matching a filter works
+generic_test!(
+    should_set_value_with_filter,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/array_of_tables.toml",
+        "bin[?name == \"other\"].path",
+        "src/bin.rs"
+    ],
+    r#"[[bin]]
+name = "tomli"
+path = "src/main.rs"
+priority = 1
+
+[[bin]]
+name = "tomli-helper"
+path = "src/helper.rs"
+priority = 2
+
+[[bin]]
+name = "other"
+path = "src/bin.rs"
+priority = 3
+"#
+);
+
+// Test if appending a value to an array works
+set_test!(
+    should_append_value_to_array,
+    "table.array[+]",
+    "4",
+    ["--type", "int"],
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}], 4]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if appending a table to an array of tables works
+set_test!(
+    should_append_table_to_array_of_tables,
+    "table.array_of_tables[+].key",
+    "value3",
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value3"
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if appending an inline table to an array works
+set_test!(
+    should_append_inline_table_to_array,
+    "table.inline_table.array_in_inline_table[+].key",
+    "value",
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [{ key = "value" }] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if appending a table to an array that does not exist creates an array of tables
+set_test!(
+    should_create_array_of_tables_when_appending,
+    "second_table.bin[+].name",
+    "tomli",
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+
+[[second_table.bin]]
+name = "tomli"
+"#
+);
+
+// Test if inserting a value at the beginning of an array shifts the other elements
+set_test!(
+    should_insert_value_at_beginning_of_array,
+    "table.array[0]",
+    "0",
+    ["--type", "int", "--insert"],
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [0, 1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if inserting a table into an array of tables works
+set_test!(
+    should_insert_table_into_array_of_tables,
+    "table.array_of_tables[1].key",
+    "value3",
+    ["--insert"],
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value3"
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if inserting a table in front of the first table of a document keeps the empty line
+// between the tables
+generic_test!(
+    should_insert_table_at_beginning_of_array_of_tables,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/array_of_tables.toml",
+        "--insert",
+        "bin[0].name",
+        "first"
+    ],
+    r#"[[bin]]
+name = "first"
+
+[[bin]]
+name = "tomli"
+path = "src/main.rs"
+priority = 1
+
+[[bin]]
+name = "tomli-helper"
+path = "src/helper.rs"
+priority = 2
+
+[[bin]]
+name = "other"
+path = "src/other.rs"
+priority = 3
+"#
+);
+
+// Test if inserting into a multiline array keeps its comments and indentation
+generic_test!(
+    should_insert_values_into_multiline_array_with_comments,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/features.toml",
+        "--insert",
+        "features.default[0]",
+        "z",
+        "--assign",
+        "features.default[3]=y",
+        "--assign",
+        "features.empty[0]=x"
+    ],
+    r#"[features]
+default = [
+    "z",
+    "a", # first
+    "b",
+    "y",
+]
+empty = ["x"]
+single = [1]
+"#
+);
+
+// Test if appending to a multiline array uses the formatting of its last element
+generic_test!(
+    should_append_value_to_multiline_array,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/features.toml",
+        "features.default[+]",
+        "z",
+        "--assign",
+        "features.empty[+]=y"
+    ],
+    r#"[features]
+default = [
+    "a", # first
+    "b",
+    "z",
+]
+empty = ["y"]
+single = [1]
+"#
+);
+
+// Test if setting the value type to a TOML array works
+set_test!(
+    should_set_value_type_to_toml_array,
+    "table.array",
+    "[\"a\", \"b\"]",
+    ["--type", "toml"],
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = ["a", "b"]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if setting the value type to a TOML inline table keeps its formatting
+set_test!(
+    should_set_value_type_to_toml_inline_table,
+    "table.dependency",
+    "{ version = \"1\",optional=true }",
+    ["--type", "toml"],
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+dependency = { version = "1",optional=true }
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if the auto type infers integers and keeps their representation
+set_test!(
+    should_infer_hexadecimal_integer,
+    "table.array[1]",
+    "0xFF",
+    ["--type", "auto"],
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 0xFF, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if the auto type infers booleans
+set_test!(
+    should_infer_boolean,
+    "table.key",
+    "true",
+    ["--type", "auto"],
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+key = true
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if the auto type infers floats
+set_test!(
+    should_infer_float,
+    "table.key",
+    "1_000.5",
+    ["--type", "auto"],
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+key = 1_000.5
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if the auto type infers datetimes
+set_test!(
+    should_infer_datetime,
+    "table.key",
+    "1979-05-27",
+    ["--type", "auto"],
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+key = 1979-05-27
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if the auto type falls back to a string
+set_test!(
+    should_fall_back_to_string,
+    "table.key",
+    "1.2.3",
+    ["--type", "auto"],
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+key = "1.2.3"
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if the type of the existing value is kept
+set_test!(
+    should_keep_type_of_existing_value,
+    "table.number",
+    "8080",
+    ["--keep-type"],
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 8080
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if the given type is used if there is no existing value
+set_test!(
+    should_use_type_if_value_does_not_exist,
+    "table.key",
+    "true",
+    ["--keep-type", "--type", "bool"],
+    r#"[table]
+key_with_decorator = "value"
+key_without_decorator ="value"
+number = 2
+inline_table = { inline_key = "inline_value", array_in_inline_table = [] }
+array = [1, 2, 3, [4, 5, 6, { name = "inline_table_in_array", another_array = [8, 9]}]]
+key = true
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[[table.array_of_tables]]
+key = "value"
+key2 = "value2"
+array = [1, 2, 3]
+
+[second_table.'brackets(more_brackets(quotes = "a", more_quotes = "b"))']
+key = "value"
+"#
+);
+
+// Test if the comments around a replaced value are kept
+generic_test!(
+    should_keep_decor_of_replaced_value,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/comments.toml",
+        "package.version",
+        "0.2.0"
+    ],
+    r#"# Package metadata
+[package]
+name = "tomli"  # The name of the binary
+version = "0.2.0" # Bumped on every release
+"#
+);
+
+// Test if the comments around a replaced value are removed with --discard-decor
+generic_test!(
+    should_discard_decor_of_replaced_value,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/comments.toml",
+        "--discard-decor",
+        "package.version",
+        "0.2.0"
+    ],
+    r#"# Package metadata
+[package]
+name = "tomli"  # The name of the binary
+version = "0.2.0"
+"#
+);
+
+// Test if setting the value type to a local time works
+generic_test!(
+    should_set_value_and_convert_it_to_time,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/comments.toml",
+        "--type",
+        "time",
+        "package.released",
+        "10:20:30"
+    ],
+    r#"# Package metadata
+[package]
+name = "tomli"  # The name of the binary
+version = "0.1.0" # Bumped on every release
+released = 10:20:30
+"#
+);
+
+// Test if setting the value type to an offset datetime works
+generic_test!(
+    should_set_value_and_convert_it_to_offset_datetime,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/comments.toml",
+        "--type",
+        "offset-datetime",
+        "package.released",
+        "2025-12-12T10:20:30+02:00"
+    ],
+    r#"# Package metadata
+[package]
+name = "tomli"  # The name of the binary
+version = "0.1.0" # Bumped on every release
+released = 2025-12-12T10:20:30+02:00
+"#
+);
+
+// Test if setting a string with the literal style works
+generic_test!(
+    should_set_string_with_literal_style,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/comments.toml",
+        "--string-style",
+        "literal",
+        "package.path",
+        r"C:\tomli\bin"
+    ],
+    r#"# Package metadata
+[package]
+name = "tomli"  # The name of the binary
+version = "0.1.0" # Bumped on every release
+path = 'C:\tomli\bin'
+"#
+);
+
+// Test if the string style is used when the auto type falls back to a string
+generic_test!(
+    should_set_auto_string_with_basic_style,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/comments.toml",
+        "--type",
+        "auto",
+        "--string-style",
+        "basic",
+        "package.path",
+        r"C:\tomli\bin"
+    ],
+    r#"# Package metadata
+[package]
+name = "tomli"  # The name of the binary
+version = "0.1.0" # Bumped on every release
+path = "C:\\tomli\\bin"
+"#
+);
+
+// Test if setting a string with the multiline basic style works
+generic_test!(
+    should_set_string_with_multiline_basic_style,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/comments.toml",
+        "--string-style",
+        "multiline-basic",
+        "package.description",
+        "A simple CLI\nfor TOML"
+    ],
+    r#"# Package metadata
+[package]
+name = "tomli"  # The name of the binary
+version = "0.1.0" # Bumped on every release
+description = """
+A simple CLI
+for TOML"""
+"#
+);
+
+// Test if the radix of an integer is kept
+generic_test!(
+    should_keep_radix_of_integer,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/comments.toml",
+        "--type",
+        "int",
+        "package.mode",
+        "0o755"
+    ],
+    r#"# Package metadata
+[package]
+name = "tomli"  # The name of the binary
+version = "0.1.0" # Bumped on every release
+mode = 0o755
+"#
+);
+
+// Test if integers can be converted to another representation with grouped digits
+generic_test!(
+    should_set_integer_as_hex_with_grouped_digits,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/comments.toml",
+        "--type",
+        "int",
+        "--int-format",
+        "hex",
+        "--group-digits",
+        "package.flags",
+        "4294967295"
+    ],
+    r#"# Package metadata
+[package]
+name = "tomli"  # The name of the binary
+version = "0.1.0" # Bumped on every release
+flags = 0xffff_ffff
+"#
+);
+
+// Test if multiple values can be set in one invocation
+generic_test!(
+    should_set_multiple_values,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/comments.toml",
+        "package.version",
+        "0.2.0",
+        "--assign",
+        "package.name=tomli2",
+        "-a",
+        "package.mode:int=0o755"
+    ],
+    r#"# Package metadata
+[package]
+name = "tomli2"  # The name of the binary
+version = "0.2.0" # Bumped on every release
+mode = 0o755
+"#
+);
+
+// Test if assignments with filters are split at the correct equal sign
+generic_test!(
+    should_set_multiple_values_with_filter,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/array_of_tables.toml",
+        "--assign",
+        "bin[?name == \"tomli\"].priority:int=9",
+        "--assign",
+        "bin[1:2].path=src/helper.rs"
+    ],
+    r#"[[bin]]
+name = "tomli"
+path = "src/main.rs"
+priority = 9
+
+[[bin]]
+name = "tomli-helper"
+path = "src/helper.rs"
+priority = 2
+
+[[bin]]
+name = "other"
+path = "src/other.rs"
+priority = 3
+"#
+);
+
 /*
 * Tests that should always fail
 */
@@ -551,3 +1443,140 @@ set_test!(
     ["--type", "datetime"],
     "Could not convert the given value to a datetime\n"
 );
+
+// Test if setting a datetime with the value type date fails
+set_test!(
+    should_fail_to_convert_datetime_to_date,
+    "table.array[1]",
+    "2025-12-12T10:20:30",
+    ["--type", "date"],
+    "The given value is a valid datetime but not of type date\n"
+);
+
+// Test if setting a local datetime with the value type offset-datetime fails
+set_test!(
+    should_fail_to_convert_local_datetime_to_offset_datetime,
+    "table.array[1]",
+    "2025-12-12T10:20:30",
+    ["--type", "offset-datetime"],
+    "The given value is a valid datetime but not of type offset-datetime\n"
+);
+
+// Test if setting a string that contains a single quote with the literal style fails
+set_test!(
+    should_fail_to_set_string_with_single_quote_as_literal,
+    "table.key_with_decorator",
+    "it's",
+    ["--string-style", "literal"],
+    "The given value can not be represented as a literal string\n"
+);
+
+// Test if converting a negative integer to hex fails
+set_test!(
+    should_fail_to_set_negative_integer_as_hex,
+    "table.number",
+    "-5",
+    ["--type", "int", "--int-format", "hex", "--"],
+    "Negative integers can only be represented in decimal\n"
+);
+
+// Test if an assignment without a value fails
+generic_test!(
+    should_fail_to_set_assignment_without_value,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/comments.toml",
+        "--assign",
+        "package.version"
+    ],
+    "error: invalid value 'package.version' for '--assign <QUERY=VALUE>': Invalid assignment \"package.version\", expected QUERY=VALUE or QUERY:TYPE=VALUE\n\nFor more information, try '--help'.\n"
+);
+
+// Test if inserting a value out of bounds fails
+set_test!(
+    should_not_insert_value_out_of_bounds,
+    "table.array[5]",
+    "value",
+    ["--insert"],
+    "Index (\"5\") is out of bounds\n"
+);
+
+// Test if inserting a value without an index fails
+set_test!(
+    should_not_insert_value_without_index,
+    "table.key",
+    "value",
+    ["--insert"],
+    r#"Invalid query for command set
+Inserting a value requires an array index in the query
+"#
+);
+
+// Test if setting the value type to an invalid TOML value fails
+set_test!(
+    should_fail_to_convert_value_to_toml,
+    "table.array[1]",
+    "[1,",
+    ["--type", "toml"],
+    r#"Could not parse the given value as a TOML value:
+
+TOML parse error at line 1, column 4
+  |
+1 | [1,
+  |    ^
+unclosed array, expected `]`
+
+"#
+);
+
+// Test if setting a table to a TOML value fails
+set_test!(
+    should_not_set_table_to_toml_value,
+    "table",
+    "{ key = 1 }",
+    ["--type", "toml"],
+    r#"Invalid query for command set
+Transforming a table or an array to a key / pair value is not allowed
+"#
+);
+
+// Test if keeping the type fails if the value can not be converted
+set_test!(
+    should_fail_to_keep_type_of_existing_value,
+    "table.number",
+    "abc",
+    ["--keep-type"],
+    "Could not convert the given value to an integer (i64)\n"
+);
+
+// Test if keeping the type of an array fails if the value is not an array
+set_test!(
+    should_fail_to_keep_type_of_existing_array,
+    "table.array",
+    "{ key = 1 }",
+    ["--keep-type"],
+    "Could not convert the given value to the type of the existing value (array)\n"
+);
+
+// Test if the number representation can not be used with strings
+set_test!(
+    should_not_format_number_of_string,
+    "table.number",
+    "255",
+    ["--int-format", "hex"],
+    "--int-format and --group-digits can only be used with the types int, float, auto or toml\n"
+);
+
+// Test if a filter that matches nothing is reported
+generic_test!(
+    should_fail_if_filter_matches_nothing,
+    vec![
+        "set",
+        "--filepath",
+        "tests/files/array_of_tables.toml",
+        "bin[?name == \"nope\"].path",
+        "src/nope.rs"
+    ],
+    "The query did not match anything: bin[?name == \"nope\"].path\n"
+);